@@ -0,0 +1,195 @@
+use anyhow::anyhow;
+use axum::{
+    response::IntoResponse,
+    routing::{delete, put},
+    Extension, Json, Router,
+};
+use axum_extra::extract::PrivateCookieJar;
+use chrono::{Duration, Utc};
+use entity::prelude::*;
+use sea_orm::{prelude::*, sea_query::Expr, Condition, ConnectionTrait, Set, TransactionTrait};
+use serde::Deserialize;
+
+use crate::{
+    auth::{
+        ensure_username_available, remove_login_cookie, validate_potential_username,
+        AuthenticatedUser, UsernameValidationError, USERNAME_CHANGE_COOLDOWN_DAYS,
+    },
+    comment::delete_comment,
+    error::{Error, Result},
+    export::{delete_user_exports, remove_export_archives, ExportSettings},
+    review::delete_reviews,
+    review_image::review_image_keys,
+    storage::{delete_stored_files, SharedStorage},
+};
+
+/// Username of the placeholder account that reviews from deleted accounts can be handed over to
+pub const DELETED_USER_USERNAME: &str = "[deleted]";
+
+/// How long an old username is held for its previous owner after they change away from it, so that
+/// nobody else can pick it up to impersonate them and they can change back if they want
+const OLD_USERNAME_RESERVATION_DAYS: i64 = 90;
+
+#[derive(Debug, Deserialize)]
+struct ChangeUsernameParams {
+    username: String,
+}
+
+async fn change_username(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Json(params): Json<ChangeUsernameParams>,
+) -> Result<impl IntoResponse> {
+    use entity::reserved_username::Column;
+
+    let user = User::find_by_id(auth.user_id)
+        .one(conn)
+        .await?
+        .ok_or(Error::Unauthorized)?;
+
+    if user.username == params.username {
+        return Ok(Json(user));
+    }
+
+    validate_potential_username(&params.username)?;
+
+    let now = Utc::now();
+    if let Some(changed_at) = user.username_changed_at {
+        if now < changed_at + Duration::days(USERNAME_CHANGE_COOLDOWN_DAYS) {
+            Err(UsernameValidationError::ChangedTooRecently)?;
+        }
+    }
+
+    let txn = conn.begin().await?;
+    ensure_username_available(&txn, &params.username, Some(user.id)).await?;
+
+    // Drop the reservation on the new name if the user is reclaiming it, and tidy up any others
+    // that have lapsed while we're here
+    ReservedUsername::delete_many()
+        .filter(
+            Condition::any()
+                .add(Column::Username.eq(params.username.clone()))
+                .add(Column::ReservedUntil.lte(now)),
+        )
+        .exec(&txn)
+        .await?;
+
+    entity::reserved_username::ActiveModel {
+        username: Set(user.username.clone()),
+        user: Set(user.id),
+        reserved_until: Set(now + Duration::days(OLD_USERNAME_RESERVATION_DAYS)),
+    }
+    .insert(&txn)
+    .await?;
+
+    let mut user: entity::user::ActiveModel = user.into();
+    user.username = Set(params.username);
+    user.username_changed_at = Set(Some(now));
+    let user = user.update(&txn).await?;
+
+    txn.commit().await?;
+
+    Ok(Json(user))
+}
+
+/// The placeholder account that reviews from deleted accounts are handed over to
+pub async fn find_deleted_user(conn: &impl ConnectionTrait) -> Result<entity::user::Model> {
+    Ok(User::find()
+        .filter(entity::user::Column::Username.eq(DELETED_USER_USERNAME))
+        .one(conn)
+        .await?
+        .ok_or_else(|| anyhow!("The deleted user placeholder account is missing"))?)
+}
+
+/// What to do with a deleted account's reviews
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ReviewDisposition {
+    /// Keep the reviews, but attribute them to the placeholder deleted user
+    Anonymise,
+    Delete,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeleteAccountParams {
+    reviews: ReviewDisposition,
+}
+
+async fn delete_account(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(export_settings): Extension<ExportSettings>,
+    Extension(storage): Extension<SharedStorage>,
+    jar: PrivateCookieJar,
+    Json(params): Json<DeleteAccountParams>,
+) -> Result<impl IntoResponse> {
+    use entity::review::Column;
+
+    let txn = conn.begin().await?;
+
+    let placeholder = find_deleted_user(&txn).await?;
+
+    let mut image_keys = Vec::new();
+    match params.reviews {
+        ReviewDisposition::Anonymise => {
+            Review::update_many()
+                .col_expr(Column::User, Expr::value(placeholder.id))
+                .filter(Column::User.eq(auth.user_id))
+                .exec(&txn)
+                .await?;
+        }
+        ReviewDisposition::Delete => {
+            let review_ids = Review::find()
+                .filter(Column::User.eq(auth.user_id))
+                .all(&txn)
+                .await?
+                .into_iter()
+                .map(|review| review.id)
+                .collect::<Vec<_>>();
+
+            image_keys = review_image_keys(&txn, review_ids.clone()).await?;
+            delete_reviews(&txn, review_ids).await?;
+        }
+    }
+
+    // Comments are deleted as if the user had done it themselves, so other people's replies stay.
+    // The ones left blanked out for their replies are handed to the placeholder, since they'd
+    // otherwise cascade away with the user and take the replies with them.
+    let comments = ReviewComment::find()
+        .filter(entity::review_comment::Column::Author.eq(auth.user_id))
+        .all(&txn)
+        .await?;
+    for comment in comments {
+        // Tidying up after an earlier comment may have already removed this one
+        if let Some(comment) = ReviewComment::find_by_id(comment.id).one(&txn).await? {
+            delete_comment(&txn, comment).await?;
+        }
+    }
+    ReviewComment::update_many()
+        .col_expr(
+            entity::review_comment::Column::Author,
+            Expr::value(placeholder.id),
+        )
+        .filter(entity::review_comment::Column::Author.eq(auth.user_id))
+        .exec(&txn)
+        .await?;
+
+    let export_archives = delete_user_exports(&txn, &export_settings, auth.user_id).await?;
+
+    // Used nonces, google logins and username reservations all cascade from the user. Any login
+    // cookies still out there are rejected once the user no longer exists.
+    User::delete_by_id(auth.user_id).exec(&txn).await?;
+
+    txn.commit().await?;
+
+    delete_stored_files(&storage, &image_keys).await;
+    remove_export_archives(&export_archives).await;
+
+    Ok((remove_login_cookie(jar), "Account deleted"))
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/account", delete(delete_account))
+        .route("/account/username", put(change_username))
+}
@@ -0,0 +1,69 @@
+use chrono::{Duration, Utc};
+use entity::{prelude::*, sea_orm_active_enums::ActivityKind};
+use sea_orm::{prelude::*, ConnectionTrait, Set};
+
+use crate::error::Result;
+
+/// Repeated edits to a list within this long of each other only show up in feeds once
+const LIST_UPDATE_COALESCE_MINUTES: i64 = 60;
+
+pub enum NewActivity {
+    ReviewCreated { review: i32, sauce: i32 },
+    ListUpdated { list: i32 },
+    SauceAdded { sauce: i32, manufacturer: i32 },
+    ManufacturerAdded { manufacturer: i32 },
+}
+
+/// Record something happening for activity feeds
+pub async fn record_activity(
+    conn: &impl ConnectionTrait,
+    actor: Option<i32>,
+    activity: NewActivity,
+) -> Result<()> {
+    use entity::activity::Column;
+
+    let now = Utc::now();
+    let mut model = entity::activity::ActiveModel {
+        actor: Set(actor),
+        created_at: Set(now),
+        ..Default::default()
+    };
+
+    match activity {
+        NewActivity::ReviewCreated { review, sauce } => {
+            model.kind = Set(ActivityKind::ReviewCreated);
+            model.review = Set(Some(review));
+            model.sauce = Set(Some(sauce));
+        }
+        NewActivity::ListUpdated { list } => {
+            let recent = Activity::find()
+                .filter(Column::Kind.eq(ActivityKind::ListUpdated))
+                .filter(Column::SauceList.eq(list))
+                .filter(Column::CreatedAt.gt(now - Duration::minutes(LIST_UPDATE_COALESCE_MINUTES)))
+                .one(conn)
+                .await?;
+            if recent.is_some() {
+                return Ok(());
+            }
+
+            model.kind = Set(ActivityKind::ListUpdated);
+            model.sauce_list = Set(Some(list));
+        }
+        NewActivity::SauceAdded {
+            sauce,
+            manufacturer,
+        } => {
+            model.kind = Set(ActivityKind::SauceAdded);
+            model.sauce = Set(Some(sauce));
+            model.manufacturer = Set(Some(manufacturer));
+        }
+        NewActivity::ManufacturerAdded { manufacturer } => {
+            model.kind = Set(ActivityKind::ManufacturerAdded);
+            model.manufacturer = Set(Some(manufacturer));
+        }
+    }
+
+    model.insert(conn).await?;
+
+    Ok(())
+}
@@ -17,16 +17,19 @@ use jsonwebtoken::{
     jwk::{AlgorithmParameters, JwkSet},
     DecodingKey, Validation,
 };
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    Set,
+};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::error::{Error, Result};
 
-const GOOGLE_CLIENT_ID: &'static str =
+const GOOGLE_CLIENT_ID: &str =
     "1029137063431-crnebmaeal8jdm85iurqoin9k6aqvccj.apps.googleusercontent.com";
 
-const LOGIN_COOKIE_NAME: &'static str = "login_cookie";
+const LOGIN_COOKIE_NAME: &str = "login_cookie";
 
 #[derive(Debug, Error)]
 pub enum TokenValidationError {
@@ -46,6 +49,7 @@ pub enum TokenValidationError {
     UnknownKey,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Deserialize)]
 pub struct Claims {
     pub sub: String,
@@ -85,7 +89,7 @@ async fn validate_token(token: &str) -> std::result::Result<Claims, TokenValidat
     validation.set_audience(&[GOOGLE_CLIENT_ID]);
     validation.set_issuer(&["https://accounts.google.com"]);
 
-    let decoded = jsonwebtoken::decode::<Claims>(&token, &decoding_key, &validation)?;
+    let decoded = jsonwebtoken::decode::<Claims>(token, &decoding_key, &validation)?;
 
     Ok(decoded.claims)
 }
@@ -105,7 +109,6 @@ async fn is_nonce_unique(conn: &DatabaseConnection, nonce: String, user_id: i32)
             nonce: Set(nonce),
             user: Set(user_id),
             used_at: Set(Utc::now()),
-            ..Default::default()
         }
         .insert(conn)
         .await?;
@@ -176,6 +179,8 @@ fn do_login(user_id: i32, jar: PrivateCookieJar) -> Result<impl IntoResponse> {
 const MIN_USERNAME_LEN: usize = 5;
 const MAX_USERNAME_LEN: usize = 25;
 
+pub const USERNAME_CHANGE_COOLDOWN_DAYS: i64 = 30;
+
 #[derive(Error, Debug)]
 pub enum UsernameValidationError {
     #[error("Usernames must be at least {} characters long", MIN_USERNAME_LEN)]
@@ -189,13 +194,21 @@ pub enum UsernameValidationError {
 
     #[error("Username already in use")]
     AlreadyTaken,
+
+    #[error(
+        "Usernames can only be changed once every {} days",
+        USERNAME_CHANGE_COOLDOWN_DAYS
+    )]
+    ChangedTooRecently,
 }
 
-fn validate_potential_username(username: &str) -> std::result::Result<(), UsernameValidationError> {
-    if username.chars().any(|c| match c {
-        'a'..='z' | '0'..='9' | '-' | '_' => false,
-        _ => true,
-    }) {
+pub fn validate_potential_username(
+    username: &str,
+) -> std::result::Result<(), UsernameValidationError> {
+    if username
+        .chars()
+        .any(|c| !matches!(c, 'a'..='z' | '0'..='9' | '-' | '_'))
+    {
         return Err(UsernameValidationError::IllegalCharacters);
     }
 
@@ -209,6 +222,35 @@ fn validate_potential_username(username: &str) -> std::result::Result<(), Userna
     Ok(())
 }
 
+/// Check that nobody else is using the username, or has recently changed away from it.
+///
+/// `user_id` is the user who wants the name, if they already have an account; a user is allowed to
+/// take back a name that is only reserved for themselves.
+pub async fn ensure_username_available(
+    conn: &impl ConnectionTrait,
+    username: &str,
+    user_id: Option<i32>,
+) -> Result<()> {
+    let existing = User::find()
+        .filter(entity::user::Column::Username.eq(username))
+        .one(conn)
+        .await?;
+
+    let reservation = ReservedUsername::find_by_id(username.to_string())
+        .filter(entity::reserved_username::Column::ReservedUntil.gt(Utc::now()))
+        .one(conn)
+        .await?;
+
+    let taken_by_other = existing.is_some()
+        || reservation.is_some_and(|reservation| Some(reservation.user) != user_id);
+
+    if taken_by_other {
+        Err(UsernameValidationError::AlreadyTaken)?
+    } else {
+        Ok(())
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct LoginParams {
     google_id_token: String,
@@ -249,6 +291,7 @@ async fn signup(
 ) -> Result<impl IntoResponse> {
     let claims = validate_token(&params.google_id_token).await?;
     validate_potential_username(&params.username)?;
+    ensure_username_available(conn, &params.username, None).await?;
     let user_id = create_user(conn, claims.sub, params.username.clone()).await;
 
     match user_id {
@@ -275,9 +318,12 @@ async fn signup(
     }
 }
 
+pub fn remove_login_cookie(jar: PrivateCookieJar) -> PrivateCookieJar {
+    jar.remove(Cookie::named(LOGIN_COOKIE_NAME))
+}
+
 async fn logout(jar: PrivateCookieJar) -> Result<impl IntoResponse> {
-    let jar = jar.remove(Cookie::named(LOGIN_COOKIE_NAME));
-    Ok(jar)
+    Ok(remove_login_cookie(jar))
 }
 
 async fn debug_login_cookie(jar: PrivateCookieJar) -> Result<impl IntoResponse> {
@@ -317,11 +363,21 @@ where
             LoginCookieData::decode_cookie_str(cookie.value()).ok_or(Error::Unauthorized)?;
 
         if cookie_data.valid_until < Utc::now() {
-            Err(Error::Unauthorized)
-        } else {
-            Ok(AuthenticatedUser {
-                user_id: cookie_data.user_id,
-            })
+            return Err(Error::Unauthorized);
         }
+
+        // Make sure the account hasn't been deleted since the cookie was issued
+        let Extension(conn) = Extension::<DatabaseConnection>::from_request(req)
+            .await
+            .map_err(|_| anyhow!("Failed to get DatabaseConnection extension"))?;
+
+        User::find_by_id(cookie_data.user_id)
+            .one(&conn)
+            .await?
+            .ok_or(Error::Unauthorized)?;
+
+        Ok(AuthenticatedUser {
+            user_id: cookie_data.user_id,
+        })
     }
 }
@@ -0,0 +1,239 @@
+use axum::{
+    extract::Path,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Extension, Json, Router,
+};
+use entity::prelude::*;
+use sea_orm::{prelude::*, PaginatorTrait};
+use serde::Serialize;
+
+use crate::{
+    error::{Error, Result},
+    markup::escape,
+    ratings::{find_axis, sauce_axis_averages, HeatBand, HEAT_AXIS_NAME, OVERALL_AXIS_NAME},
+    sauce::find_visible_sauce,
+    SiteSettings, API_PREFIX,
+};
+
+const LABEL: &str = "hot sauce rating";
+
+/// Badges are cheap to revalidate thanks to their ETag, so they're only cached briefly
+const CACHE_CONTROL: &str = "public, max-age=300";
+
+const BADGE_HEIGHT: f64 = 20.0;
+/// Space either side of the text in each half of the badge
+const PADDING: f64 = 5.0;
+
+/// Approximate width of some text in 11px Verdana, which is close enough to size the badge
+fn text_width(text: &str) -> f64 {
+    text.chars()
+        .map(|c| match c {
+            'i' | 'j' | 'l' | '.' | ',' | ':' | ';' | '\'' | '|' | '!' => 3.5,
+            ' ' | '·' => 3.9,
+            'f' | 'r' | 't' => 4.5,
+            'm' | 'w' | 'M' | 'W' => 10.0,
+            c if c.is_ascii_uppercase() => 7.5,
+            c if c.is_ascii_digit() => 7.0,
+            _ => 6.5,
+        })
+        .sum()
+}
+
+/// Colour a score from red through to green by how far along the overall axis it is
+fn score_colour(fraction: Option<f64>) -> &'static str {
+    match fraction {
+        None => "#9f9f9f",
+        Some(f) if f >= 0.8 => "#4c1",
+        Some(f) if f >= 0.6 => "#97ca00",
+        Some(f) if f >= 0.4 => "#dfb317",
+        Some(f) if f >= 0.2 => "#fe7d37",
+        Some(_) => "#e05d44",
+    }
+}
+
+/// Draw a flat two-part badge in the same style as shields.io
+fn render_badge(label: &str, message: &str, colour: &str) -> String {
+    let label_width = (text_width(label) + 2.0 * PADDING).round();
+    let message_width = (text_width(message) + 2.0 * PADDING).round();
+    let width = label_width + message_width;
+    let label_x = label_width / 2.0;
+    let message_x = label_width + message_width / 2.0;
+    let label = escape(label);
+    let message = escape(message);
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{BADGE_HEIGHT}" role="img" aria-label="{label}: {message}">
+<title>{label}: {message}</title>
+<linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>
+<clipPath id="r"><rect width="{width}" height="{BADGE_HEIGHT}" rx="3" fill="#fff"/></clipPath>
+<g clip-path="url(#r)"><rect width="{label_width}" height="{BADGE_HEIGHT}" fill="#555"/><rect x="{label_width}" width="{message_width}" height="{BADGE_HEIGHT}" fill="{colour}"/><rect width="{width}" height="{BADGE_HEIGHT}" fill="url(#s)"/></g>
+<g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">
+<text x="{label_x}" y="15" fill="#010101" fill-opacity=".3">{label}</text><text x="{label_x}" y="14">{label}</text>
+<text x="{message_x}" y="15" fill="#010101" fill-opacity=".3">{message}</text><text x="{message_x}" y="14">{message}</text>
+</g>
+</svg>
+"##
+    )
+}
+
+/// The score, review count and heat of a sauce, e.g. "7.4/10 · 23 reviews · hot", along with the
+/// colour the badge for it is drawn in
+pub async fn score_summary(
+    conn: &DatabaseConnection,
+    sauce_id: i32,
+) -> Result<(String, &'static str)> {
+    let review_count = Review::find()
+        .filter(entity::review::Column::Sauce.eq(sauce_id))
+        .filter(entity::review::Column::HiddenAt.is_null())
+        .count(conn)
+        .await?;
+    if review_count == 0 {
+        return Ok(("no reviews yet".to_string(), score_colour(None)));
+    }
+
+    let averages = sauce_axis_averages(conn, sauce_id, None).await?;
+    let mut parts = Vec::new();
+    let mut fraction = None;
+
+    if let Some(axis) = find_axis(conn, OVERALL_AXIS_NAME).await? {
+        if let Some(overall) = averages.get(&axis.id) {
+            parts.push(format!("{overall:.1}/{}", axis.max_value));
+            fraction = Some((overall - axis.min_value) / (axis.max_value - axis.min_value));
+        }
+    }
+    parts.push(match review_count {
+        1 => "1 review".to_string(),
+        n => format!("{n} reviews"),
+    });
+    if let Some(axis) = find_axis(conn, HEAT_AXIS_NAME).await? {
+        if let Some(heat) = averages.get(&axis.id) {
+            let band = HeatBand::from_fraction(
+                (heat - axis.min_value) / (axis.max_value - axis.min_value),
+            );
+            parts.push(band.name().to_string());
+        }
+    }
+
+    Ok((parts.join(" · "), score_colour(fraction)))
+}
+
+/// 64-bit FNV-1a. Unlike `DefaultHasher` its output is fixed, so ETags stay the same across
+/// restarts and Rust releases.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// Whether an `If-None-Match` header lists the given ETag
+fn etag_matches(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim().trim_start_matches("W/"))
+        .any(|tag| tag == etag || tag == "*")
+}
+
+/// A badge showing a sauce's community score, for other sites to embed. The path is the sauce id
+/// with an `.svg` extension.
+async fn sauce_badge(
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(file_name): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response> {
+    let sauce_id: i32 = file_name
+        .strip_suffix(".svg")
+        .and_then(|id| id.parse().ok())
+        .ok_or(Error::NotFound)?;
+    let sauce = find_visible_sauce(conn, sauce_id).await?;
+
+    let (message, colour) = score_summary(conn, sauce.id).await?;
+    let svg = render_badge(LABEL, &message, colour);
+
+    let etag = format!("\"{:016x}\"", fnv1a(svg.as_bytes()));
+
+    if etag_matches(&headers, &etag) {
+        return Ok((
+            StatusCode::NOT_MODIFIED,
+            [
+                (header::ETAG, etag),
+                (header::CACHE_CONTROL, CACHE_CONTROL.to_string()),
+            ],
+        )
+            .into_response());
+    }
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "image/svg+xml".to_string()),
+            (header::ETAG, etag),
+            (header::CACHE_CONTROL, CACHE_CONTROL.to_string()),
+        ],
+        svg,
+    )
+        .into_response())
+}
+
+#[derive(Debug, Serialize)]
+struct BadgeEmbed {
+    image_url: String,
+    link_url: String,
+    html: String,
+    markdown: String,
+}
+
+/// Ready-made snippets for putting a sauce's badge on another site, linking back to the sauce
+async fn sauce_badge_embed(
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(site): Extension<SiteSettings>,
+    Path(sauce_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let sauce = find_visible_sauce(conn, sauce_id).await?;
+
+    let image_url = format!(
+        "{}{API_PREFIX}/badge/sauce/{}.svg",
+        site.public_url, sauce.id
+    );
+    let link_url = format!("{}/sauce/{}", site.public_url, sauce.id);
+    let alt = format!("{} {LABEL}", sauce.name);
+
+    Ok(Json(BadgeEmbed {
+        html: format!(
+            r#"<a href="{}"><img src="{}" alt="{}"></a>"#,
+            escape(&link_url),
+            escape(&image_url),
+            escape(&alt)
+        ),
+        markdown: format!(
+            "[![{}]({image_url})]({link_url})",
+            alt.replace(['[', ']'], "")
+        ),
+        image_url,
+        link_url,
+    }))
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/badge/sauce/:sauce", get(sauce_badge))
+        .route("/badge/sauce/:sauce/embed", get(sauce_badge_embed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fnv1a_matches_reference_values() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+}
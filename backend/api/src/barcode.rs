@@ -0,0 +1,184 @@
+use axum::{
+    extract::Path,
+    response::IntoResponse,
+    routing::{get, put},
+    Extension, Json, Router,
+};
+use chrono::Utc;
+use entity::prelude::*;
+use sea_orm::{prelude::*, QueryOrder, Set};
+
+use crate::{
+    auth::AuthenticatedUser,
+    error::{Error, Result},
+    moderation::find_moderator,
+    sauce::{find_visible_sauce, sauce_responses},
+};
+
+/// Check a scanned UPC-A or EAN-13 barcode and turn it into the 13 digit form it's stored in. A
+/// UPC-A code is an EAN-13 code with a leading zero left off, so both scan to the same sauce.
+fn normalise_barcode(code: &str) -> Result<String> {
+    let code = code.trim();
+    let invalid = || Error::BadRequest(format!("\"{code}\" isn't a valid UPC-A or EAN-13 barcode"));
+
+    if !code.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let code = match code.len() {
+        12 => format!("0{code}"),
+        13 => code.to_string(),
+        _ => return Err(invalid()),
+    };
+
+    // Digits alternate between weights of 1 and 3, and the check digit brings the total up to a
+    // multiple of ten
+    let digits: Vec<u32> = code.chars().filter_map(|c| c.to_digit(10)).collect();
+    let sum: u32 = digits[..12]
+        .iter()
+        .enumerate()
+        .map(|(i, digit)| match i % 2 {
+            0 => *digit,
+            _ => digit * 3,
+        })
+        .sum();
+
+    match (10 - sum % 10) % 10 == digits[12] {
+        true => Ok(code),
+        false => Err(invalid()),
+    }
+}
+
+/// The sauce a scanned barcode belongs to. Codes nobody has added yet are a 404, so the client
+/// can offer to add the sauce instead.
+async fn sauce_by_barcode(
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(code): Path<String>,
+) -> Result<impl IntoResponse> {
+    let code = normalise_barcode(&code)?;
+
+    let sauce = SauceBarcode::find_by_id(code)
+        .find_also_related(Sauce)
+        .one(conn)
+        .await?
+        .and_then(|(_, sauce)| sauce)
+        .filter(|sauce| sauce.hidden_at.is_none())
+        .ok_or(Error::NotFound)?;
+
+    let mut responses = sauce_responses(conn, vec![sauce]).await?;
+    Ok(Json(responses.pop()))
+}
+
+async fn barcode_list(
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(sauce_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let sauce = find_visible_sauce(conn, sauce_id).await?;
+
+    let barcodes = SauceBarcode::find()
+        .filter(entity::sauce_barcode::Column::Sauce.eq(sauce.id))
+        .order_by_asc(entity::sauce_barcode::Column::CreatedAt)
+        .all(conn)
+        .await?;
+
+    Ok(Json(barcodes))
+}
+
+/// Add another barcode to a sauce, for a different bottle size or region
+async fn barcode_add(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path((sauce_id, code)): Path<(i32, String)>,
+) -> Result<impl IntoResponse> {
+    let sauce = find_visible_sauce(conn, sauce_id).await?;
+    let code = normalise_barcode(&code)?;
+
+    if let Some(existing) = SauceBarcode::find_by_id(code.clone()).one(conn).await? {
+        return match existing.sauce == sauce.id {
+            true => Ok(Json(existing)),
+            false => Err(Error::BadRequest(
+                "That barcode already belongs to another sauce".to_string(),
+            )),
+        };
+    }
+
+    let barcode = entity::sauce_barcode::ActiveModel {
+        code: Set(code),
+        sauce: Set(sauce.id),
+        added_by: Set(Some(auth.user_id)),
+        created_at: Set(Utc::now()),
+    }
+    .insert(conn)
+    .await?;
+
+    Ok(Json(barcode))
+}
+
+/// Barcodes can be removed by whoever added them, or by a moderator
+async fn barcode_delete(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path((sauce_id, code)): Path<(i32, String)>,
+) -> Result<impl IntoResponse> {
+    let code = normalise_barcode(&code)?;
+
+    let barcode = SauceBarcode::find_by_id(code)
+        .filter(entity::sauce_barcode::Column::Sauce.eq(sauce_id))
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+    if barcode.added_by != Some(auth.user_id) {
+        find_moderator(conn, &auth).await?;
+    }
+
+    SauceBarcode::delete_by_id(barcode.code).exec(conn).await?;
+
+    Ok(Json(()))
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/sauce/by_barcode/:code", get(sauce_by_barcode))
+        .route("/sauce/:sauce_id/barcodes", get(barcode_list))
+        .route(
+            "/sauce/:sauce_id/barcodes/:code",
+            put(barcode_add).delete(barcode_delete),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalise_barcode;
+
+    #[test]
+    fn accepts_valid_ean13() {
+        assert_eq!(normalise_barcode("4006381333931").unwrap(), "4006381333931");
+        assert_eq!(
+            normalise_barcode(" 5901234123457 ").unwrap(),
+            "5901234123457"
+        );
+    }
+
+    #[test]
+    fn upc_a_is_padded_to_ean13() {
+        assert_eq!(normalise_barcode("036000291452").unwrap(), "0036000291452");
+        assert_eq!(
+            normalise_barcode("036000291452").unwrap(),
+            normalise_barcode("0036000291452").unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_bad_check_digits() {
+        assert!(normalise_barcode("4006381333932").is_err());
+        assert!(normalise_barcode("036000291453").is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_codes() {
+        assert!(normalise_barcode("").is_err());
+        assert!(normalise_barcode("03600029145").is_err());
+        assert!(normalise_barcode("40063813339311").is_err());
+        assert!(normalise_barcode("4006381-33931").is_err());
+        assert!(normalise_barcode("４００６３８１３３３９３１").is_err());
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::Path,
+    response::IntoResponse,
+    routing::{delete, get},
+    Extension, Json, Router,
+};
+use chrono::{NaiveDate, Utc};
+use entity::prelude::*;
+use sea_orm::{
+    prelude::*, sea_query::Expr, ConnectionTrait, FromQueryResult, QueryOrder, QuerySelect, Set,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::AuthenticatedUser,
+    error::{Error, Result},
+    moderation::find_moderator,
+    ratings::{axis_averages, AxisAverage},
+    sauce::find_visible_sauce,
+};
+
+const MAX_LOT_NUMBER_LENGTH: usize = 64;
+
+/// Check that a review's batch, if it has one, is a batch of the sauce being reviewed
+pub async fn validate_batch(
+    conn: &impl ConnectionTrait,
+    sauce_id: i32,
+    batch_id: Option<i32>,
+) -> Result<()> {
+    let batch_id = match batch_id {
+        Some(batch_id) => batch_id,
+        None => return Ok(()),
+    };
+
+    SauceBatch::find_by_id(batch_id)
+        .filter(entity::sauce_batch::Column::Sauce.eq(sauce_id))
+        .one(conn)
+        .await?
+        .map(|_| ())
+        .ok_or_else(|| Error::BadRequest(format!("Unknown batch {batch_id} for this sauce")))
+}
+
+/// A sauce's batches, oldest label first. Batches without a label date go last.
+async fn sauce_batches(
+    conn: &DatabaseConnection,
+    sauce_id: i32,
+) -> Result<Vec<entity::sauce_batch::Model>> {
+    use entity::sauce_batch::Column;
+
+    Ok(SauceBatch::find()
+        .filter(Column::Sauce.eq(sauce_id))
+        .order_by_asc(Expr::col(Column::LabelDate).is_null())
+        .order_by_asc(Column::LabelDate)
+        .order_by_asc(Column::Id)
+        .all(conn)
+        .await?)
+}
+
+async fn batch_list(
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(sauce_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let sauce = find_visible_sauce(conn, sauce_id).await?;
+    Ok(Json(sauce_batches(conn, sauce.id).await?))
+}
+
+#[derive(Debug, Deserialize)]
+struct NewBatch {
+    label_date: Option<NaiveDate>,
+    lot_number: Option<String>,
+    #[serde(default)]
+    new_recipe: bool,
+}
+
+async fn batch_insert(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(sauce_id): Path<i32>,
+    Json(new): Json<NewBatch>,
+) -> Result<impl IntoResponse> {
+    let sauce = find_visible_sauce(conn, sauce_id).await?;
+
+    let lot_number = new
+        .lot_number
+        .map(|lot_number| lot_number.trim().to_string())
+        .filter(|lot_number| !lot_number.is_empty());
+    if lot_number
+        .as_ref()
+        .is_some_and(|lot_number| lot_number.chars().count() > MAX_LOT_NUMBER_LENGTH)
+    {
+        return Err(Error::BadRequest(format!(
+            "Lot numbers can be at most {MAX_LOT_NUMBER_LENGTH} characters"
+        )));
+    }
+    if new.label_date.is_none() && lot_number.is_none() {
+        return Err(Error::BadRequest(
+            "A batch needs a label date or a lot number".to_string(),
+        ));
+    }
+
+    let batch = entity::sauce_batch::ActiveModel {
+        sauce: Set(sauce.id),
+        label_date: Set(new.label_date),
+        lot_number: Set(lot_number),
+        new_recipe: Set(new.new_recipe),
+        added_by: Set(Some(auth.user_id)),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await?;
+
+    Ok(Json(batch))
+}
+
+/// Batches can be removed by whoever added them, or by a moderator. Reviews of the batch are kept,
+/// but no longer say which batch they were of.
+async fn batch_delete(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(batch_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let batch = SauceBatch::find_by_id(batch_id)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+    if batch.added_by != Some(auth.user_id) {
+        find_moderator(conn, &auth).await?;
+    }
+
+    let txn = conn.begin().await?;
+
+    Review::update_many()
+        .col_expr(
+            entity::review::Column::Batch,
+            Expr::value(Option::<i32>::None),
+        )
+        .filter(entity::review::Column::Batch.eq(batch.id))
+        .exec(&txn)
+        .await?;
+    SauceBatch::delete_by_id(batch.id).exec(&txn).await?;
+
+    txn.commit().await?;
+
+    Ok(Json(()))
+}
+
+#[derive(Debug, FromQueryResult)]
+struct BatchRating {
+    review: i32,
+    batch: Option<i32>,
+    rating_axis: i32,
+    rating: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct BatchStats {
+    /// `None` for reviews that don't say which batch they were of
+    batch: Option<entity::sauce_batch::Model>,
+    review_count: usize,
+    axis_averages: Vec<AxisAverage>,
+}
+
+#[derive(Debug, Serialize)]
+struct SauceStats {
+    sauce: i32,
+    review_count: usize,
+    axis_averages: Vec<AxisAverage>,
+    /// The same averages for each batch, in the same order as the batch list
+    batches: Vec<BatchStats>,
+}
+
+#[derive(Debug, Default)]
+struct RatingGroup {
+    reviews: Vec<i32>,
+    by_axis: HashMap<i32, Vec<f64>>,
+}
+
+impl RatingGroup {
+    fn add(&mut self, rating: &BatchRating) {
+        if !self.reviews.contains(&rating.review) {
+            self.reviews.push(rating.review);
+        }
+        self.by_axis
+            .entry(rating.rating_axis)
+            .or_default()
+            .push(rating.rating);
+    }
+}
+
+/// Average scores for a sauce, overall and broken down by batch, so a change of recipe can be
+/// compared against what came before
+async fn sauce_stats(
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(sauce_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let sauce = find_visible_sauce(conn, sauce_id).await?;
+
+    let ratings = ReviewRating::find()
+        .select_only()
+        .column(entity::review_rating::Column::Review)
+        .column_as(entity::review::Column::Batch, "batch")
+        .column(entity::review_rating::Column::RatingAxis)
+        .column(entity::review_rating::Column::Rating)
+        .inner_join(Review)
+        .filter(entity::review::Column::Sauce.eq(sauce.id))
+        .filter(entity::review::Column::HiddenAt.is_null())
+        .into_model::<BatchRating>()
+        .all(conn)
+        .await?;
+
+    let mut overall = RatingGroup::default();
+    let mut by_batch: HashMap<Option<i32>, RatingGroup> = HashMap::new();
+    for rating in &ratings {
+        overall.add(rating);
+        by_batch.entry(rating.batch).or_default().add(rating);
+    }
+
+    let axes = RatingAxis::find().all(conn).await?;
+    let group_stats = |batch: Option<entity::sauce_batch::Model>, group: RatingGroup| BatchStats {
+        batch,
+        review_count: group.reviews.len(),
+        axis_averages: axis_averages(&axes, &group.by_axis),
+    };
+
+    let mut batches: Vec<BatchStats> = sauce_batches(conn, sauce.id)
+        .await?
+        .into_iter()
+        .map(|batch| {
+            let group = by_batch.remove(&Some(batch.id)).unwrap_or_default();
+            group_stats(Some(batch), group)
+        })
+        .collect();
+    if let Some(group) = by_batch.remove(&None) {
+        batches.push(group_stats(None, group));
+    }
+
+    Ok(Json(SauceStats {
+        sauce: sauce.id,
+        review_count: overall.reviews.len(),
+        axis_averages: axis_averages(&axes, &overall.by_axis),
+        batches,
+    }))
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route(
+            "/sauce/:sauce_id/batches",
+            get(batch_list).post(batch_insert),
+        )
+        .route("/sauce/:sauce_id/stats", get(sauce_stats))
+        .route("/batch/:batch_id", delete(batch_delete))
+}
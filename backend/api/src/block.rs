@@ -0,0 +1,276 @@
+use std::collections::HashSet;
+
+use axum::{
+    extract::Path,
+    response::IntoResponse,
+    routing::{get, put},
+    Extension, Json, Router,
+};
+use chrono::{DateTime, Utc};
+use entity::{prelude::*, sea_orm_active_enums::BlockKind};
+use sea_orm::{
+    prelude::*,
+    sea_query::{Query as SubQuery, SelectStatement},
+    Condition, ConnectionTrait, QueryOrder, Set, TransactionTrait,
+};
+use serde::Serialize;
+
+use crate::{
+    auth::AuthenticatedUser,
+    error::{Error, Result},
+    profile::find_user_by_username,
+};
+
+/// Users the viewer has blocked or muted, whose content is left out of what they're shown
+pub fn ignored_users(viewer_id: i32) -> SelectStatement {
+    SubQuery::select()
+        .column(entity::user_block::Column::Blocked)
+        .from(UserBlock)
+        .and_where(entity::user_block::Column::Blocker.eq(viewer_id))
+        .to_owned()
+}
+
+/// Like [`ignored_users`], for filtering results that have already been loaded
+pub async fn ignored_user_ids(
+    conn: &DatabaseConnection,
+    viewer: Option<&AuthenticatedUser>,
+) -> Result<HashSet<i32>> {
+    let viewer = match viewer {
+        Some(viewer) => viewer,
+        None => return Ok(HashSet::new()),
+    };
+
+    Ok(UserBlock::find()
+        .filter(entity::user_block::Column::Blocker.eq(viewer.user_id))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|block| block.blocked)
+        .collect())
+}
+
+/// Pages made up of one user's content act as if that user doesn't exist for a viewer who has
+/// blocked or muted them. Their basic profile is still shown, so they can be found to unblock.
+pub async fn ensure_not_ignored(
+    conn: &DatabaseConnection,
+    viewer: Option<&AuthenticatedUser>,
+    user_id: i32,
+) -> Result<()> {
+    match ignored_user_ids(conn, viewer).await?.contains(&user_id) {
+        true => Err(Error::NotFound),
+        false => Ok(()),
+    }
+}
+
+/// Blocks work both ways, so neither user can interact with the other's content
+pub async fn ensure_not_blocked(
+    conn: &impl ConnectionTrait,
+    user_id: i32,
+    other_id: i32,
+) -> Result<()> {
+    use entity::user_block::Column;
+
+    let blocked = UserBlock::find()
+        .filter(Column::Kind.eq(BlockKind::Block))
+        .filter(
+            Condition::any()
+                .add(
+                    Condition::all()
+                        .add(Column::Blocker.eq(user_id))
+                        .add(Column::Blocked.eq(other_id)),
+                )
+                .add(
+                    Condition::all()
+                        .add(Column::Blocker.eq(other_id))
+                        .add(Column::Blocked.eq(user_id)),
+                ),
+        )
+        .one(conn)
+        .await?
+        .is_some();
+
+    match blocked {
+        true => Err(Error::Forbidden),
+        false => Ok(()),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct BlockResponse {
+    id: i32,
+    username: String,
+    kind: BlockKind,
+    since: DateTime<Utc>,
+}
+
+/// Block or mute a user, replacing whichever the caller had already chosen for them
+async fn set_block(
+    conn: &DatabaseConnection,
+    auth: &AuthenticatedUser,
+    username: &str,
+    kind: BlockKind,
+) -> Result<BlockResponse> {
+    let user = find_user_by_username(conn, username).await?;
+    if user.id == auth.user_id {
+        return Err(Error::BadRequest(
+            "You can't block or mute yourself".to_string(),
+        ));
+    }
+
+    let txn = conn.begin().await?;
+
+    let block = match UserBlock::find_by_id((auth.user_id, user.id))
+        .one(&txn)
+        .await?
+    {
+        Some(existing) if existing.kind == kind => existing,
+        Some(existing) => {
+            let mut block: entity::user_block::ActiveModel = existing.into();
+            block.kind = Set(kind);
+            block.update(&txn).await?
+        }
+        None => {
+            entity::user_block::ActiveModel {
+                blocker: Set(auth.user_id),
+                blocked: Set(user.id),
+                kind: Set(kind),
+                created_at: Set(Utc::now()),
+            }
+            .insert(&txn)
+            .await?
+        }
+    };
+
+    // Muting just quietens someone, but blocking also cuts any follows between the two
+    if kind == BlockKind::Block {
+        use entity::follow::Column;
+
+        Follow::delete_many()
+            .filter(
+                Condition::any()
+                    .add(
+                        Condition::all()
+                            .add(Column::Follower.eq(auth.user_id))
+                            .add(Column::Followee.eq(user.id)),
+                    )
+                    .add(
+                        Condition::all()
+                            .add(Column::Follower.eq(user.id))
+                            .add(Column::Followee.eq(auth.user_id)),
+                    ),
+            )
+            .exec(&txn)
+            .await?;
+    }
+
+    txn.commit().await?;
+
+    Ok(BlockResponse {
+        id: user.id,
+        username: user.username,
+        kind: block.kind,
+        since: block.created_at,
+    })
+}
+
+async fn remove_block(
+    conn: &DatabaseConnection,
+    auth: &AuthenticatedUser,
+    username: &str,
+    kind: BlockKind,
+) -> Result<()> {
+    use entity::user_block::Column;
+
+    let user = find_user_by_username(conn, username).await?;
+    let result = UserBlock::delete_many()
+        .filter(Column::Blocker.eq(auth.user_id))
+        .filter(Column::Blocked.eq(user.id))
+        .filter(Column::Kind.eq(kind))
+        .exec(conn)
+        .await?;
+
+    if result.rows_affected == 0 {
+        return Err(Error::NotFound);
+    }
+
+    Ok(())
+}
+
+async fn block(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(username): Path<String>,
+) -> Result<impl IntoResponse> {
+    Ok(Json(
+        set_block(conn, &auth, &username, BlockKind::Block).await?,
+    ))
+}
+
+async fn unblock(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(username): Path<String>,
+) -> Result<impl IntoResponse> {
+    remove_block(conn, &auth, &username, BlockKind::Block).await?;
+    Ok(Json(()))
+}
+
+async fn mute(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(username): Path<String>,
+) -> Result<impl IntoResponse> {
+    Ok(Json(
+        set_block(conn, &auth, &username, BlockKind::Mute).await?,
+    ))
+}
+
+async fn unmute(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(username): Path<String>,
+) -> Result<impl IntoResponse> {
+    remove_block(conn, &auth, &username, BlockKind::Mute).await?;
+    Ok(Json(()))
+}
+
+/// Everyone the caller has blocked or muted, most recent first
+async fn blocks(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+) -> Result<impl IntoResponse> {
+    use entity::user_block::Column;
+
+    let blocks = UserBlock::find()
+        .filter(Column::Blocker.eq(auth.user_id))
+        .order_by_desc(Column::CreatedAt)
+        .all(conn)
+        .await?;
+
+    let users = User::find()
+        .filter(entity::user::Column::Id.is_in(blocks.iter().map(|block| block.blocked)))
+        .all(conn)
+        .await?;
+
+    let responses: Vec<BlockResponse> = blocks
+        .into_iter()
+        .filter_map(|block| {
+            let user = users.iter().find(|user| user.id == block.blocked)?;
+            Some(BlockResponse {
+                id: user.id,
+                username: user.username.clone(),
+                kind: block.kind,
+                since: block.created_at,
+            })
+        })
+        .collect();
+
+    Ok(Json(responses))
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/user/:username/block", put(block).delete(unblock))
+        .route("/user/:username/mute", put(mute).delete(unmute))
+        .route("/blocks", get(blocks))
+}
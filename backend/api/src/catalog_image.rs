@@ -0,0 +1,503 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use axum::{
+    extract::{Multipart, Path},
+    http::header,
+    response::IntoResponse,
+    routing::{delete, get, post, put},
+    Extension, Json, Router,
+};
+use chrono::{DateTime, Utc};
+use entity::{
+    prelude::*,
+    sea_orm_active_enums::{ModerationActionKind, ModerationTarget},
+};
+use rand::Rng;
+use sea_orm::{
+    prelude::*,
+    sea_query::{Expr, IntoCondition},
+    Condition, ConnectionTrait, QueryOrder, Set, TransactionTrait,
+};
+use serde::Serialize;
+
+use crate::{
+    auth::AuthenticatedUser,
+    error::{Error, Result},
+    images::{process_image, read_upload, ImageSettings, ImageSize, CONTENT_TYPE},
+    moderation::{find_moderator, hold_for_moderation, is_moderator},
+    storage::{delete_stored_files, SharedStorage},
+    API_PREFIX,
+};
+
+/// Approved images are the same for everyone, but held ones are only shown to their uploader and
+/// moderators
+const PUBLIC_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+const PRIVATE_CACHE_CONTROL: &str = "private, no-store";
+
+/// What a catalog image is attached to
+#[derive(Debug, Clone, Copy)]
+enum CatalogEntry {
+    Sauce(i32),
+    Manufacturer(i32),
+}
+
+impl CatalogEntry {
+    fn images(self) -> Condition {
+        use entity::catalog_image::Column;
+
+        Condition::all().add(match self {
+            CatalogEntry::Sauce(id) => Column::Sauce.eq(id),
+            CatalogEntry::Manufacturer(id) => Column::Manufacturer.eq(id),
+        })
+    }
+
+    /// Images can only be added to entries that haven't been hidden by a moderator
+    async fn ensure_visible(self, conn: &DatabaseConnection) -> Result<()> {
+        let found = match self {
+            CatalogEntry::Sauce(id) => Sauce::find_by_id(id)
+                .filter(entity::sauce::Column::HiddenAt.is_null())
+                .one(conn)
+                .await?
+                .is_some(),
+            CatalogEntry::Manufacturer(id) => Manufacturer::find_by_id(id)
+                .filter(entity::manufacturer::Column::HiddenAt.is_null())
+                .one(conn)
+                .await?
+                .is_some(),
+        };
+
+        match found {
+            true => Ok(()),
+            false => Err(Error::NotFound),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CatalogImageResponse {
+    pub id: i32,
+    pub sauce: Option<i32>,
+    pub manufacturer: Option<i32>,
+    pub width: i32,
+    pub height: i32,
+    pub is_cover: bool,
+    /// Still waiting for a moderator to approve it
+    pub hidden: bool,
+    pub thumbnail_url: String,
+    pub medium_url: String,
+    pub full_url: String,
+    pub created_at: DateTime<Utc>,
+}
+
+fn image_url(image_id: i32, size: ImageSize) -> String {
+    format!("{API_PREFIX}/catalog_image/{image_id}/{}", size.name())
+}
+
+impl From<entity::catalog_image::Model> for CatalogImageResponse {
+    fn from(image: entity::catalog_image::Model) -> Self {
+        Self {
+            id: image.id,
+            sauce: image.sauce,
+            manufacturer: image.manufacturer,
+            width: image.width,
+            height: image.height,
+            is_cover: image.is_cover,
+            hidden: image.hidden_at.is_some(),
+            thumbnail_url: image_url(image.id, ImageSize::Thumbnail),
+            medium_url: image_url(image.id, ImageSize::Medium),
+            full_url: image_url(image.id, ImageSize::Full),
+            created_at: image.created_at,
+        }
+    }
+}
+
+/// Where one size of an image is kept
+fn file_key(storage_key: &str, size: ImageSize) -> String {
+    format!("{storage_key}_{}.webp", size.name())
+}
+
+/// Storage keys of every file belonging to the images that match `condition`, for cleaning up
+/// after they've been deleted
+pub async fn catalog_image_keys(
+    conn: &impl ConnectionTrait,
+    condition: impl IntoCondition,
+) -> Result<Vec<String>> {
+    Ok(CatalogImage::find()
+        .filter(condition)
+        .all(conn)
+        .await?
+        .into_iter()
+        .flat_map(|image| ImageSize::ALL.map(|size| file_key(&image.storage_key, size)))
+        .collect())
+}
+
+/// The image to show for each entry: whichever a moderator picked as the cover, otherwise the
+/// first one approved
+async fn covers(
+    conn: &DatabaseConnection,
+    column: entity::catalog_image::Column,
+    ids: Vec<i32>,
+    entry_id: fn(&entity::catalog_image::Model) -> Option<i32>,
+) -> Result<HashMap<i32, CatalogImageResponse>> {
+    let images = CatalogImage::find()
+        .filter(column.is_in(ids))
+        .filter(entity::catalog_image::Column::HiddenAt.is_null())
+        .order_by_asc(entity::catalog_image::Column::Id)
+        .all(conn)
+        .await?;
+
+    let mut covers: HashMap<i32, entity::catalog_image::Model> = HashMap::new();
+    for image in images {
+        let id = match entry_id(&image) {
+            Some(id) => id,
+            None => continue,
+        };
+
+        match covers.entry(id) {
+            Entry::Vacant(entry) => {
+                entry.insert(image);
+            }
+            Entry::Occupied(mut entry) if image.is_cover && !entry.get().is_cover => {
+                entry.insert(image);
+            }
+            Entry::Occupied(_) => {}
+        }
+    }
+
+    Ok(covers
+        .into_iter()
+        .map(|(id, image)| (id, image.into()))
+        .collect())
+}
+
+pub async fn sauce_covers(
+    conn: &DatabaseConnection,
+    sauce_ids: Vec<i32>,
+) -> Result<HashMap<i32, CatalogImageResponse>> {
+    covers(
+        conn,
+        entity::catalog_image::Column::Sauce,
+        sauce_ids,
+        |image| image.sauce,
+    )
+    .await
+}
+
+pub async fn manufacturer_covers(
+    conn: &DatabaseConnection,
+    manufacturer_ids: Vec<i32>,
+) -> Result<HashMap<i32, CatalogImageResponse>> {
+    covers(
+        conn,
+        entity::catalog_image::Column::Manufacturer,
+        manufacturer_ids,
+        |image| image.manufacturer,
+    )
+    .await
+}
+
+/// Store a new image of a sauce or manufacturer. Anything not uploaded by a moderator is held
+/// until one has approved it.
+async fn upload(
+    conn: &DatabaseConnection,
+    storage: &SharedStorage,
+    settings: &ImageSettings,
+    auth: &AuthenticatedUser,
+    entry: CatalogEntry,
+    mut multipart: Multipart,
+) -> Result<CatalogImageResponse> {
+    entry.ensure_visible(conn).await?;
+
+    let mut data = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| Error::BadRequest(e.to_string()))?
+    {
+        if field.name() == Some("image") {
+            data = Some(read_upload(field, settings.max_upload_bytes).await?);
+            break;
+        }
+    }
+    let data = data
+        .ok_or_else(|| Error::BadRequest("Upload an image in the \"image\" field".to_string()))?;
+
+    let encoded = tokio::task::spawn_blocking(move || process_image(&data, ImageSize::ALL))
+        .await
+        .map_err(anyhow::Error::from)??;
+
+    let id: u128 = rand::thread_rng().gen();
+    let storage_key = format!("catalog/{id:032x}");
+    let [.., full] = &encoded;
+    let (width, height) = (full.width, full.height);
+    let keys = ImageSize::ALL.map(|size| file_key(&storage_key, size));
+
+    let (sauce, manufacturer) = match entry {
+        CatalogEntry::Sauce(id) => (Some(id), None),
+        CatalogEntry::Manufacturer(id) => (None, Some(id)),
+    };
+
+    let held = !is_moderator(conn, Some(auth)).await?;
+
+    // Nothing refers to the files until the image is saved, so they're removed again if that fails
+    let stored: Result<_> = async {
+        for (key, image) in keys.iter().zip(encoded) {
+            storage.put(key, image.data).await?;
+        }
+
+        let txn = conn.begin().await?;
+        let image = entity::catalog_image::ActiveModel {
+            sauce: Set(sauce),
+            manufacturer: Set(manufacturer),
+            uploaded_by: Set(Some(auth.user_id)),
+            storage_key: Set(storage_key),
+            content_type: Set(CONTENT_TYPE.to_string()),
+            width: Set(width as i32),
+            height: Set(height as i32),
+            is_cover: Set(false),
+            hidden_at: Set(None),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        if held {
+            hold_for_moderation(
+                &txn,
+                ModerationTarget::CatalogImage,
+                image.id,
+                "New images are checked by a moderator before they're shown",
+            )
+            .await?;
+        }
+        txn.commit().await?;
+
+        Ok(image)
+    }
+    .await;
+    let image = match stored {
+        Ok(image) => image,
+        Err(e) => {
+            delete_stored_files(storage, &keys).await;
+            return Err(e);
+        }
+    };
+
+    let image = CatalogImage::find_by_id(image.id)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    Ok(image.into())
+}
+
+async fn upload_sauce_image(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(storage): Extension<SharedStorage>,
+    Extension(settings): Extension<ImageSettings>,
+    Path(sauce_id): Path<i32>,
+    multipart: Multipart,
+) -> Result<impl IntoResponse> {
+    let entry = CatalogEntry::Sauce(sauce_id);
+    Ok(Json(
+        upload(conn, &storage, &settings, &auth, entry, multipart).await?,
+    ))
+}
+
+async fn upload_manufacturer_image(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(storage): Extension<SharedStorage>,
+    Extension(settings): Extension<ImageSettings>,
+    Path(manufacturer_id): Path<i32>,
+    multipart: Multipart,
+) -> Result<impl IntoResponse> {
+    let entry = CatalogEntry::Manufacturer(manufacturer_id);
+    Ok(Json(
+        upload(conn, &storage, &settings, &auth, entry, multipart).await?,
+    ))
+}
+
+/// Approved images of an entry, oldest first, along with any of the viewer's own that are still
+/// waiting for approval
+async fn list(
+    conn: &DatabaseConnection,
+    viewer: Option<&AuthenticatedUser>,
+    entry: CatalogEntry,
+) -> Result<Vec<CatalogImageResponse>> {
+    use entity::catalog_image::Column;
+
+    entry.ensure_visible(conn).await?;
+
+    let mut visible = Condition::any().add(Column::HiddenAt.is_null());
+    if let Some(viewer) = viewer {
+        visible = visible.add(Column::UploadedBy.eq(viewer.user_id));
+    }
+
+    Ok(CatalogImage::find()
+        .filter(entry.images().add(visible))
+        .order_by_asc(Column::Id)
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(CatalogImageResponse::from)
+        .collect())
+}
+
+async fn sauce_images(
+    auth: Option<AuthenticatedUser>,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(sauce_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    Ok(Json(
+        list(conn, auth.as_ref(), CatalogEntry::Sauce(sauce_id)).await?,
+    ))
+}
+
+async fn manufacturer_images(
+    auth: Option<AuthenticatedUser>,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(manufacturer_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let entry = CatalogEntry::Manufacturer(manufacturer_id);
+    Ok(Json(list(conn, auth.as_ref(), entry).await?))
+}
+
+async fn image_file(
+    auth: Option<AuthenticatedUser>,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(storage): Extension<SharedStorage>,
+    Path((image_id, size)): Path<(i32, ImageSize)>,
+) -> Result<impl IntoResponse> {
+    let image = CatalogImage::find_by_id(image_id)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    let cache_control = match image.hidden_at {
+        None => PUBLIC_CACHE_CONTROL,
+        Some(_) => {
+            let uploader = auth
+                .as_ref()
+                .is_some_and(|auth| image.uploaded_by == Some(auth.user_id));
+            if !uploader && !is_moderator(conn, auth.as_ref()).await? {
+                return Err(Error::NotFound);
+            }
+            PRIVATE_CACHE_CONTROL
+        }
+    };
+
+    let data = storage
+        .get(&file_key(&image.storage_key, size))
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, image.content_type),
+            (header::CACHE_CONTROL, cache_control.to_string()),
+        ],
+        data,
+    ))
+}
+
+/// Make an approved image the one shown for its sauce or manufacturer
+async fn set_cover(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(image_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    use entity::catalog_image::Column;
+
+    let moderator = find_moderator(conn, &auth).await?;
+
+    let image = CatalogImage::find_by_id(image_id)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+    if image.hidden_at.is_some() {
+        return Err(Error::BadRequest(
+            "Only approved images can be used as a cover".to_string(),
+        ));
+    }
+
+    let entry = match (image.sauce, image.manufacturer) {
+        (Some(sauce), _) => CatalogEntry::Sauce(sauce),
+        (None, Some(manufacturer)) => CatalogEntry::Manufacturer(manufacturer),
+        (None, None) => return Err(Error::NotFound),
+    };
+
+    let txn = conn.begin().await?;
+
+    CatalogImage::update_many()
+        .col_expr(Column::IsCover, Expr::col(Column::Id).eq(image.id))
+        .filter(entry.images())
+        .exec(&txn)
+        .await?;
+
+    entity::moderation_action::ActiveModel {
+        moderator: Set(Some(moderator.id)),
+        kind: Set(ModerationActionKind::SetCover),
+        target_kind: Set(ModerationTarget::CatalogImage),
+        target_id: Set(image.id),
+        target_user: Set(image.uploaded_by),
+        note: Set(None),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+
+    txn.commit().await?;
+
+    let image = CatalogImage::find_by_id(image.id)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    Ok(Json(CatalogImageResponse::from(image)))
+}
+
+/// Uploaders can take back their own images
+async fn delete_image(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(storage): Extension<SharedStorage>,
+    Path(image_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let image = CatalogImage::find_by_id(image_id)
+        .filter(entity::catalog_image::Column::UploadedBy.eq(auth.user_id))
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    let keys = ImageSize::ALL.map(|size| file_key(&image.storage_key, size));
+    CatalogImage::delete_by_id(image.id).exec(conn).await?;
+    delete_stored_files(&storage, &keys).await;
+
+    Ok(Json(()))
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/sauce/:sauce_id/images", get(sauce_images))
+        .route(
+            "/manufacturer/:manufacturer_id/images",
+            get(manufacturer_images),
+        )
+        .route("/catalog_image/:image_id", delete(delete_image))
+        .route("/catalog_image/:image_id/cover", put(set_cover))
+        .route("/catalog_image/:image_id/:size", get(image_file))
+}
+
+/// Uploads are kept apart from the other routes since they take a lot longer to handle
+pub fn upload_router() -> Router {
+    Router::new()
+        .route("/sauce/:sauce_id/images", post(upload_sauce_image))
+        .route(
+            "/manufacturer/:manufacturer_id/images",
+            post(upload_manufacturer_image),
+        )
+}
@@ -0,0 +1,306 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::{
+    extract::Path,
+    response::IntoResponse,
+    routing::{get, put},
+    Extension, Json, Router,
+};
+use chrono::{DateTime, Utc};
+use entity::{prelude::*, sea_orm_active_enums::ModerationTarget};
+use sea_orm::{prelude::*, ConnectionTrait, QueryOrder, Set, TransactionTrait};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::AuthenticatedUser,
+    block::{ensure_not_blocked, ignored_user_ids},
+    error::{Error, Result},
+    moderation::hold_for_moderation,
+    review::find_visible_review,
+    screening::Screening,
+};
+
+const MAX_COMMENT_LENGTH: usize = 2000;
+
+#[derive(Debug, Serialize)]
+struct CommentResponse {
+    id: i32,
+    review: i32,
+    parent: Option<i32>,
+
+    /// Missing once the comment has been deleted or hidden by a moderator
+    author: Option<i32>,
+    username: Option<String>,
+    body: Option<String>,
+
+    created_at: DateTime<Utc>,
+    edited_at: Option<DateTime<Utc>>,
+    deleted: bool,
+    hidden: bool,
+    replies: Vec<CommentResponse>,
+}
+
+/// Arrange a review's comments into threads, each level oldest first. Comments by `ignored` users
+/// are blanked out like hidden ones.
+fn comment_tree(
+    comments: Vec<entity::review_comment::Model>,
+    usernames: &HashMap<i32, String>,
+    ignored: &HashSet<i32>,
+) -> Vec<CommentResponse> {
+    let mut children: HashMap<Option<i32>, Vec<entity::review_comment::Model>> = HashMap::new();
+    for comment in comments {
+        children.entry(comment.parent).or_default().push(comment);
+    }
+
+    fn build(
+        parent: Option<i32>,
+        children: &mut HashMap<Option<i32>, Vec<entity::review_comment::Model>>,
+        usernames: &HashMap<i32, String>,
+        ignored: &HashSet<i32>,
+    ) -> Vec<CommentResponse> {
+        children
+            .remove(&parent)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|comment| {
+                let deleted = comment.deleted_at.is_some();
+                let hidden = comment.hidden_at.is_some() || ignored.contains(&comment.author);
+                let shown = !deleted && !hidden;
+                CommentResponse {
+                    id: comment.id,
+                    review: comment.review,
+                    parent: comment.parent,
+                    author: shown.then_some(comment.author),
+                    username: shown
+                        .then(|| usernames.get(&comment.author).cloned())
+                        .flatten(),
+                    body: shown.then_some(comment.body),
+                    created_at: comment.created_at,
+                    edited_at: comment.edited_at,
+                    deleted,
+                    hidden,
+                    replies: build(Some(comment.id), children, usernames, ignored),
+                }
+            })
+            .collect()
+    }
+
+    build(None, &mut children, usernames, ignored)
+}
+
+async fn review_comments(
+    auth: Option<AuthenticatedUser>,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(review_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    use entity::review_comment::Column;
+
+    let review = find_visible_review(conn, auth.as_ref(), review_id).await?;
+
+    let comments = ReviewComment::find()
+        .filter(Column::Review.eq(review.id))
+        .order_by_asc(Column::CreatedAt)
+        .order_by_asc(Column::Id)
+        .all(conn)
+        .await?;
+
+    let usernames: HashMap<i32, String> = User::find()
+        .filter(entity::user::Column::Id.is_in(comments.iter().map(|comment| comment.author)))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user.username))
+        .collect();
+
+    let ignored = ignored_user_ids(conn, auth.as_ref()).await?;
+
+    Ok(Json(comment_tree(comments, &usernames, &ignored)))
+}
+
+fn validate_body(body: &str) -> Result<()> {
+    if body.trim().is_empty() {
+        Err(Error::BadRequest("Comment must not be empty".to_string()))
+    } else if body.chars().count() > MAX_COMMENT_LENGTH {
+        Err(Error::BadRequest(format!(
+            "Comment must be at most {MAX_COMMENT_LENGTH} characters"
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct NewComment {
+    body: String,
+
+    /// The comment being replied to, if any
+    parent: Option<i32>,
+}
+
+async fn comment_insert(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(screening): Extension<Screening>,
+    Path(review_id): Path<i32>,
+    Json(new): Json<NewComment>,
+) -> Result<impl IntoResponse> {
+    validate_body(&new.body)?;
+
+    let review = find_visible_review(conn, Some(&auth), review_id).await?;
+    ensure_not_blocked(conn, auth.user_id, review.user).await?;
+
+    if let Some(parent) = new.parent {
+        let parent = ReviewComment::find_by_id(parent)
+            .filter(entity::review_comment::Column::Review.eq(review.id))
+            .one(conn)
+            .await?
+            .ok_or(Error::NotFound)?;
+
+        if parent.deleted_at.is_some() || parent.hidden_at.is_some() {
+            return Err(Error::BadRequest(
+                "Can't reply to a deleted comment".to_string(),
+            ));
+        }
+        ensure_not_blocked(conn, auth.user_id, parent.author).await?;
+    }
+
+    let held = screening.check(conn, auth.user_id, Some(&new.body)).await?;
+
+    let txn = conn.begin().await?;
+    let comment = entity::review_comment::ActiveModel {
+        review: Set(review.id),
+        parent: Set(new.parent),
+        author: Set(auth.user_id),
+        body: Set(new.body),
+        created_at: Set(Utc::now()),
+        edited_at: Set(None),
+        deleted_at: Set(None),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+    if let Some(reason) = &held {
+        hold_for_moderation(&txn, ModerationTarget::Comment, comment.id, &reason.message).await?;
+    }
+    txn.commit().await?;
+
+    let comment = ReviewComment::find_by_id(comment.id)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+    Ok(Json(comment))
+}
+
+async fn find_own_comment(
+    conn: &DatabaseConnection,
+    auth: &AuthenticatedUser,
+    comment_id: i32,
+) -> Result<entity::review_comment::Model> {
+    ReviewComment::find_by_id(comment_id)
+        .filter(entity::review_comment::Column::Author.eq(auth.user_id))
+        .filter(entity::review_comment::Column::DeletedAt.is_null())
+        // Hidden comments stay as the moderator left them
+        .filter(entity::review_comment::Column::HiddenAt.is_null())
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)
+}
+
+#[derive(Debug, Deserialize)]
+struct CommentParams {
+    body: String,
+}
+
+async fn comment_update(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(screening): Extension<Screening>,
+    Path(comment_id): Path<i32>,
+    Json(params): Json<CommentParams>,
+) -> Result<impl IntoResponse> {
+    validate_body(&params.body)?;
+
+    let comment = find_own_comment(conn, &auth, comment_id).await?;
+    let held = screening.check_edit(&params.body)?;
+
+    let txn = conn.begin().await?;
+    let mut comment: entity::review_comment::ActiveModel = comment.into();
+    comment.body = Set(params.body);
+    comment.edited_at = Set(Some(Utc::now()));
+    let comment = comment.update(&txn).await?;
+    if let Some(reason) = &held {
+        hold_for_moderation(&txn, ModerationTarget::Comment, comment.id, &reason.message).await?;
+    }
+    txn.commit().await?;
+
+    let comment = ReviewComment::find_by_id(comment.id)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+    Ok(Json(comment))
+}
+
+async fn has_replies(conn: &impl ConnectionTrait, comment_id: i32) -> Result<bool> {
+    Ok(ReviewComment::find()
+        .filter(entity::review_comment::Column::Parent.eq(comment_id))
+        .one(conn)
+        .await?
+        .is_some())
+}
+
+/// Comments without replies are removed outright. Ones with replies are blanked out instead, so
+/// the rest of the thread still makes sense.
+pub async fn delete_comment(
+    conn: &impl ConnectionTrait,
+    comment: entity::review_comment::Model,
+) -> Result<()> {
+    if has_replies(conn, comment.id).await? {
+        let mut comment: entity::review_comment::ActiveModel = comment.into();
+        comment.body = Set(String::new());
+        comment.deleted_at = Set(Some(Utc::now()));
+        comment.update(conn).await?;
+        return Ok(());
+    }
+
+    ReviewComment::delete_by_id(comment.id).exec(conn).await?;
+
+    // Blanked out comments only stick around while they still have replies
+    let mut parent_id = comment.parent;
+    while let Some(id) = parent_id {
+        let parent = match ReviewComment::find_by_id(id).one(conn).await? {
+            Some(parent) if parent.deleted_at.is_some() => parent,
+            _ => break,
+        };
+        if has_replies(conn, parent.id).await? {
+            break;
+        }
+
+        ReviewComment::delete_by_id(parent.id).exec(conn).await?;
+        parent_id = parent.parent;
+    }
+
+    Ok(())
+}
+
+async fn comment_delete(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(comment_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let comment = find_own_comment(conn, &auth, comment_id).await?;
+    delete_comment(conn, comment).await?;
+
+    Ok(Json(()))
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route(
+            "/review/:review_id/comments",
+            get(review_comments).post(comment_insert),
+        )
+        .route(
+            "/comment/:comment_id",
+            put(comment_update).delete(comment_delete),
+        )
+}
@@ -0,0 +1,308 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::{extract::Query, response::IntoResponse, routing::get, Extension, Json, Router};
+use entity::prelude::*;
+use sea_orm::{prelude::*, FromQueryResult, QuerySelect};
+use serde::Serialize;
+
+use crate::{
+    account::find_deleted_user,
+    auth::AuthenticatedUser,
+    block::ignored_user_ids,
+    error::{Error, Result},
+    profile::ensure_profile_visible,
+    ratings::{find_axis, mean, OVERALL_AXIS_NAME},
+    sauce::{sauce_responses, SauceResponse},
+};
+
+const MAX_COMPARED_SAUCES: usize = 4;
+
+/// Gaps between the highest and lowest average on an axis of at least this fraction of the axis's
+/// range are flagged as notable
+const NOTABLE_DIFFERENCE: f64 = 0.1;
+
+#[derive(Debug, FromQueryResult)]
+struct CompareRating {
+    user: i32,
+    sauce: i32,
+    rating_axis: i32,
+    rating: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct SauceAxisScore {
+    sauce: i32,
+    average: Option<f64>,
+    count: usize,
+    /// How far below the highest average on this axis the sauce is. Whether higher is better
+    /// depends on the axis, e.g. a higher Heat is only better for some people.
+    difference_from_highest: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+struct AxisComparison {
+    rating_axis: i32,
+    name: String,
+    /// In the same order as the sauces were asked for
+    sauces: Vec<SauceAxisScore>,
+    /// The sauce with the highest average, unless it's tied or only one sauce has been rated
+    highest: Option<i32>,
+    spread: Option<f64>,
+    notable: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct RankedSauce {
+    sauce: i32,
+    rating: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct ComparisonReviewer {
+    user: i32,
+    username: String,
+    /// The reviewer's overall ratings, best first
+    ranking: Vec<RankedSauce>,
+}
+
+/// How the reviewers who rated every sauce split between a pair of them on their overall ratings
+#[derive(Debug, Serialize)]
+struct HeadToHead {
+    sauce_a: i32,
+    sauce_b: i32,
+    a_preferred: usize,
+    b_preferred: usize,
+    tied: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct Comparison {
+    sauces: Vec<SauceResponse>,
+    axes: Vec<AxisComparison>,
+    /// Everyone who gave all of the sauces an overall rating, including reviewers whose profiles
+    /// aren't listed
+    reviewer_count: usize,
+    reviewers: Vec<ComparisonReviewer>,
+    head_to_head: Vec<HeadToHead>,
+}
+
+/// Read the repeated `sauce` parameters of `?sauce=1&sauce=2`
+fn compared_sauce_ids(params: &[(String, String)]) -> Result<Vec<i32>> {
+    let mut ids = Vec::new();
+    for (key, value) in params {
+        if key != "sauce" {
+            continue;
+        }
+        let id: i32 = value
+            .parse()
+            .map_err(|_| Error::BadRequest(format!("\"{value}\" isn't a sauce id")))?;
+        if ids.contains(&id) {
+            return Err(Error::BadRequest(format!(
+                "Sauce {id} was given more than once"
+            )));
+        }
+        ids.push(id);
+    }
+
+    if !(2..=MAX_COMPARED_SAUCES).contains(&ids.len()) {
+        return Err(Error::BadRequest(format!(
+            "Between 2 and {MAX_COMPARED_SAUCES} sauces can be compared"
+        )));
+    }
+
+    Ok(ids)
+}
+
+fn compare_axis(
+    axis: &entity::rating_axis::Model,
+    sauce_ids: &[i32],
+    ratings: &HashMap<(i32, i32), Vec<f64>>,
+) -> AxisComparison {
+    let averages: Vec<(i32, Option<f64>, usize)> = sauce_ids
+        .iter()
+        .map(|&sauce| {
+            let values = ratings
+                .get(&(sauce, axis.id))
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            (sauce, mean(values), values.len())
+        })
+        .collect();
+
+    let rated: Vec<(i32, f64)> = averages
+        .iter()
+        .filter_map(|&(sauce, average, _)| Some((sauce, average?)))
+        .collect();
+    let max = rated.iter().map(|&(_, average)| average).reduce(f64::max);
+    let min = rated.iter().map(|&(_, average)| average).reduce(f64::min);
+
+    let spread = match rated.len() {
+        0 | 1 => None,
+        _ => max.zip(min).map(|(max, min)| max - min),
+    };
+    let at_max: Vec<i32> = rated
+        .iter()
+        .filter(|&&(_, average)| Some(average) == max)
+        .map(|&(sauce, _)| sauce)
+        .collect();
+    let highest = match (spread, at_max.as_slice()) {
+        (Some(_), [sauce]) => Some(*sauce),
+        _ => None,
+    };
+
+    AxisComparison {
+        rating_axis: axis.id,
+        name: axis.name.clone(),
+        sauces: averages
+            .into_iter()
+            .map(|(sauce, average, count)| SauceAxisScore {
+                sauce,
+                average,
+                count,
+                difference_from_highest: max.zip(average).map(|(max, average)| max - average),
+            })
+            .collect(),
+        highest,
+        spread,
+        notable: spread
+            .is_some_and(|spread| spread >= NOTABLE_DIFFERENCE * (axis.max_value - axis.min_value)),
+    }
+}
+
+/// Side by side scores for a few sauces, to help choose between them
+async fn compare_sauces(
+    auth: Option<AuthenticatedUser>,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Query(params): Query<Vec<(String, String)>>,
+) -> Result<impl IntoResponse> {
+    let sauce_ids = compared_sauce_ids(&params)?;
+
+    let mut sauces = Sauce::find()
+        .filter(entity::sauce::Column::Id.is_in(sauce_ids.clone()))
+        .filter(entity::sauce::Column::HiddenAt.is_null())
+        .all(conn)
+        .await?;
+    if sauces.len() != sauce_ids.len() {
+        return Err(Error::NotFound);
+    }
+    sauces.sort_by_key(|sauce| sauce_ids.iter().position(|&id| id == sauce.id));
+
+    let ratings = ReviewRating::find()
+        .select_only()
+        .column_as(entity::review::Column::User, "user")
+        .column_as(entity::review::Column::Sauce, "sauce")
+        .column(entity::review_rating::Column::RatingAxis)
+        .column(entity::review_rating::Column::Rating)
+        .inner_join(Review)
+        .filter(entity::review::Column::Sauce.is_in(sauce_ids.clone()))
+        .filter(entity::review::Column::HiddenAt.is_null())
+        .into_model::<CompareRating>()
+        .all(conn)
+        .await?;
+
+    let mut by_sauce_axis: HashMap<(i32, i32), Vec<f64>> = HashMap::new();
+    for rating in &ratings {
+        by_sauce_axis
+            .entry((rating.sauce, rating.rating_axis))
+            .or_default()
+            .push(rating.rating);
+    }
+
+    let axes: Vec<AxisComparison> = RatingAxis::find()
+        .all(conn)
+        .await?
+        .iter()
+        .map(|axis| compare_axis(axis, &sauce_ids, &by_sauce_axis))
+        .collect();
+
+    // Someone who reviewed a sauce more than once is counted by the average of their reviews. The
+    // deleted user placeholder holds reviews from many different people, so it isn't a reviewer.
+    let overall_axis = find_axis(conn, OVERALL_AXIS_NAME).await?;
+    let deleted_user = find_deleted_user(conn).await?;
+    let mut overall_by_user: HashMap<i32, HashMap<i32, Vec<f64>>> = HashMap::new();
+    for rating in &ratings {
+        if rating.user == deleted_user.id {
+            continue;
+        }
+        if Some(rating.rating_axis) == overall_axis.as_ref().map(|axis| axis.id) {
+            overall_by_user
+                .entry(rating.user)
+                .or_default()
+                .entry(rating.sauce)
+                .or_default()
+                .push(rating.rating);
+        }
+    }
+    let common: HashMap<i32, HashMap<i32, f64>> = overall_by_user
+        .into_iter()
+        .filter(|(_, by_sauce)| by_sauce.len() == sauce_ids.len())
+        .map(|(user, by_sauce)| {
+            let averages = by_sauce
+                .into_iter()
+                .filter_map(|(sauce, values)| Some((sauce, mean(&values)?)))
+                .collect();
+            (user, averages)
+        })
+        .collect();
+
+    let mut head_to_head = Vec::new();
+    for (i, &sauce_a) in sauce_ids.iter().enumerate() {
+        for &sauce_b in &sauce_ids[i + 1..] {
+            let mut record = HeadToHead {
+                sauce_a,
+                sauce_b,
+                a_preferred: 0,
+                b_preferred: 0,
+                tied: 0,
+            };
+            for averages in common.values() {
+                match averages[&sauce_a].total_cmp(&averages[&sauce_b]) {
+                    std::cmp::Ordering::Greater => record.a_preferred += 1,
+                    std::cmp::Ordering::Less => record.b_preferred += 1,
+                    std::cmp::Ordering::Equal => record.tied += 1,
+                }
+            }
+            head_to_head.push(record);
+        }
+    }
+
+    let ignored = ignored_user_ids(conn, auth.as_ref()).await?;
+    let common_ids: HashSet<i32> = common.keys().copied().collect();
+    let mut reviewers: Vec<ComparisonReviewer> = User::find()
+        .filter(entity::user::Column::Id.is_in(common_ids))
+        .all(conn)
+        .await?
+        .into_iter()
+        .filter(|user| !ignored.contains(&user.id))
+        .filter(|user| ensure_profile_visible(auth.as_ref(), user).is_ok())
+        .map(|user| {
+            let mut ranking: Vec<RankedSauce> = sauce_ids
+                .iter()
+                .map(|&sauce| RankedSauce {
+                    sauce,
+                    rating: common[&user.id][&sauce],
+                })
+                .collect();
+            ranking.sort_by(|a, b| b.rating.total_cmp(&a.rating));
+
+            ComparisonReviewer {
+                user: user.id,
+                username: user.username,
+                ranking,
+            }
+        })
+        .collect();
+    reviewers.sort_by(|a, b| a.username.cmp(&b.username));
+
+    Ok(Json(Comparison {
+        sauces: sauce_responses(conn, sauces).await?,
+        axes,
+        reviewer_count: common.len(),
+        reviewers,
+        head_to_head,
+    }))
+}
+
+pub fn router() -> Router {
+    Router::new().route("/compare", get(compare_sauces))
+}
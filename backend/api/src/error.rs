@@ -1,8 +1,11 @@
 use axum::{http::StatusCode, response::IntoResponse, Json};
-use serde_json::json;
+use serde_json::{json, Value};
 use thiserror::Error;
 
-use crate::auth::{TokenValidationError, UsernameValidationError};
+use crate::{
+    auth::{TokenValidationError, UsernameValidationError},
+    screening::ScreeningReason,
+};
 
 #[allow(dead_code)]
 #[derive(Error, Debug)]
@@ -33,6 +36,15 @@ pub enum Error {
 
     #[error("The given credentials were insufficient to perform this action")]
     Forbidden,
+
+    #[error("The requested resource does not exist")]
+    NotFound,
+
+    #[error("The request was invalid")]
+    BadRequest(String),
+
+    #[error("The submitted content was rejected")]
+    ContentRejected(ScreeningReason),
 }
 
 impl Error {
@@ -47,15 +59,19 @@ impl Error {
                 }
             }
             Error::Unauthorized | Error::NoSuchAccount => StatusCode::UNAUTHORIZED,
-            Error::UsernameValidationError(_) => StatusCode::BAD_REQUEST,
+            Error::UsernameValidationError(_) | Error::BadRequest(_) => StatusCode::BAD_REQUEST,
             Error::Forbidden => StatusCode::FORBIDDEN,
+            Error::NotFound => StatusCode::NOT_FOUND,
+            Error::ContentRejected(_) => StatusCode::UNPROCESSABLE_ENTITY,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 
-    fn detailed_message(&self) -> Option<String> {
+    fn details(&self) -> Option<Value> {
         match self {
-            Error::UsernameValidationError(e) => Some(e.to_string()),
+            Error::UsernameValidationError(e) => Some(e.to_string().into()),
+            Error::BadRequest(msg) => Some(msg.clone().into()),
+            Error::ContentRejected(reason) => Some(json!(reason)),
             _ => None,
         }
     }
@@ -66,7 +82,7 @@ impl IntoResponse for Error {
         log::error!("{self:?}");
         let msg = json!({
             "error": self.to_string(),
-            "details": self.detailed_message(),
+            "details": self.details(),
         });
 
         (self.status_code(), Json(msg)).into_response()
@@ -0,0 +1,435 @@
+use std::{
+    io::{Cursor, Seek, Write},
+    path::PathBuf,
+};
+
+use axum::{
+    extract::Path,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Extension, Json, Router,
+};
+use chrono::{DateTime, Utc};
+use entity::{prelude::*, sea_orm_active_enums::ExportStatus};
+use sea_orm::{prelude::*, Condition, ConnectionTrait, PaginatorTrait, QueryOrder, Set};
+use serde::Serialize;
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+use crate::{
+    auth::AuthenticatedUser,
+    error::{Error, Result},
+    API_PREFIX,
+};
+
+/// Accounts with more reviews than this have their export built in the background rather than
+/// while the request waits
+const SYNC_EXPORT_MAX_REVIEWS: usize = 200;
+
+#[derive(Debug, Clone)]
+pub struct ExportSettings {
+    /// Directory that exports built in the background are written to
+    pub dir: PathBuf,
+}
+
+impl ExportSettings {
+    fn archive_path(&self, export_id: i32) -> PathBuf {
+        self.dir.join(format!("{export_id}.zip"))
+    }
+}
+
+/// Everything stored about a single user
+#[derive(Debug, Serialize)]
+struct UserData {
+    exported_at: DateTime<Utc>,
+    user: entity::user::Model,
+    google_logins: Vec<entity::user_google_login::Model>,
+    used_nonces: Vec<entity::used_nonce::Model>,
+    reserved_usernames: Vec<entity::reserved_username::Model>,
+    reviews: Vec<entity::review::Model>,
+    review_ratings: Vec<entity::review_rating::Model>,
+    review_images: Vec<entity::review_image::Model>,
+    shelf_entries: Vec<entity::shelf_entry::Model>,
+    sauce_lists: Vec<entity::sauce_list::Model>,
+    sauce_list_entries: Vec<entity::sauce_list_entry::Model>,
+    hosted_tasting_sessions: Vec<entity::tasting_session::Model>,
+    tasting_participations: Vec<entity::tasting_participant::Model>,
+    tasting_scorecards: Vec<entity::tasting_scorecard::Model>,
+    tasting_scorecard_ratings: Vec<entity::tasting_scorecard_rating::Model>,
+    follows: Vec<entity::follow::Model>,
+    activities: Vec<entity::activity::Model>,
+    review_comments: Vec<entity::review_comment::Model>,
+    review_votes: Vec<entity::review_vote::Model>,
+    reports: Vec<entity::report::Model>,
+    moderation_actions: Vec<entity::moderation_action::Model>,
+    blocks: Vec<entity::user_block::Model>,
+    catalog_images: Vec<entity::catalog_image::Model>,
+    sauce_barcodes: Vec<entity::sauce_barcode::Model>,
+    sauce_batches: Vec<entity::sauce_batch::Model>,
+    price_observations: Vec<entity::price_observation::Model>,
+}
+
+async fn collect_user_data(conn: &DatabaseConnection, user_id: i32) -> Result<UserData> {
+    let user = User::find_by_id(user_id)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    let reviews = Review::find()
+        .filter(entity::review::Column::User.eq(user_id))
+        .order_by_asc(entity::review::Column::Id)
+        .all(conn)
+        .await?;
+
+    let review_ratings = ReviewRating::find()
+        .filter(entity::review_rating::Column::Review.is_in(reviews.iter().map(|review| review.id)))
+        .order_by_asc(entity::review_rating::Column::Id)
+        .all(conn)
+        .await?;
+
+    let review_images = ReviewImage::find()
+        .filter(entity::review_image::Column::Review.is_in(reviews.iter().map(|review| review.id)))
+        .order_by_asc(entity::review_image::Column::Id)
+        .all(conn)
+        .await?;
+
+    let sauce_lists = SauceList::find()
+        .filter(entity::sauce_list::Column::User.eq(user_id))
+        .order_by_asc(entity::sauce_list::Column::Id)
+        .all(conn)
+        .await?;
+
+    let sauce_list_entries = SauceListEntry::find()
+        .filter(
+            entity::sauce_list_entry::Column::List.is_in(sauce_lists.iter().map(|list| list.id)),
+        )
+        .order_by_asc(entity::sauce_list_entry::Column::List)
+        .order_by_asc(entity::sauce_list_entry::Column::Position)
+        .all(conn)
+        .await?;
+
+    let tasting_scorecards = user.find_related(TastingScorecard).all(conn).await?;
+    let tasting_scorecard_ratings = TastingScorecardRating::find()
+        .filter(
+            entity::tasting_scorecard_rating::Column::Scorecard
+                .is_in(tasting_scorecards.iter().map(|scorecard| scorecard.id)),
+        )
+        .all(conn)
+        .await?;
+
+    Ok(UserData {
+        exported_at: Utc::now(),
+        google_logins: user.find_related(UserGoogleLogin).all(conn).await?,
+        used_nonces: user.find_related(UsedNonce).all(conn).await?,
+        reserved_usernames: user.find_related(ReservedUsername).all(conn).await?,
+        shelf_entries: user.find_related(ShelfEntry).all(conn).await?,
+        hosted_tasting_sessions: user.find_related(TastingSession).all(conn).await?,
+        tasting_participations: user.find_related(TastingParticipant).all(conn).await?,
+        follows: Follow::find()
+            .filter(entity::follow::Column::Follower.eq(user_id))
+            .all(conn)
+            .await?,
+        activities: user.find_related(Activity).all(conn).await?,
+        review_comments: user.find_related(ReviewComment).all(conn).await?,
+        review_votes: user.find_related(ReviewVote).all(conn).await?,
+        reports: user.find_related(Report).all(conn).await?,
+        // Actions the user took as a moderator, and ones taken against them
+        moderation_actions: ModerationAction::find()
+            .filter(
+                Condition::any()
+                    .add(entity::moderation_action::Column::Moderator.eq(user_id))
+                    .add(entity::moderation_action::Column::TargetUser.eq(user_id)),
+            )
+            .all(conn)
+            .await?,
+        blocks: UserBlock::find()
+            .filter(entity::user_block::Column::Blocker.eq(user_id))
+            .all(conn)
+            .await?,
+        catalog_images: user.find_related(CatalogImage).all(conn).await?,
+        sauce_barcodes: user.find_related(SauceBarcode).all(conn).await?,
+        sauce_batches: user.find_related(SauceBatch).all(conn).await?,
+        price_observations: user.find_related(PriceObservation).all(conn).await?,
+        reviews,
+        review_ratings,
+        review_images,
+        sauce_lists,
+        sauce_list_entries,
+        tasting_scorecards,
+        tasting_scorecard_ratings,
+        user,
+    })
+}
+
+fn write_csv<W, T>(zip: &mut ZipWriter<W>, name: &str, rows: &[T]) -> anyhow::Result<()>
+where
+    W: Write + Seek,
+    T: Serialize,
+{
+    zip.start_file(
+        name,
+        FileOptions::default().compression_method(CompressionMethod::Deflated),
+    )?;
+
+    let mut writer = csv::Writer::from_writer(&mut *zip);
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Build a zip archive containing all of the data as a single JSON document, plus a CSV file for
+/// each table
+fn build_archive(data: &UserData) -> anyhow::Result<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+
+    zip.start_file(
+        "export.json",
+        FileOptions::default().compression_method(CompressionMethod::Deflated),
+    )?;
+    serde_json::to_writer_pretty(&mut zip, data)?;
+
+    write_csv(&mut zip, "user.csv", std::slice::from_ref(&data.user))?;
+    write_csv(&mut zip, "user_google_login.csv", &data.google_logins)?;
+    write_csv(&mut zip, "used_nonce.csv", &data.used_nonces)?;
+    write_csv(&mut zip, "reserved_username.csv", &data.reserved_usernames)?;
+    write_csv(&mut zip, "review.csv", &data.reviews)?;
+    write_csv(&mut zip, "review_rating.csv", &data.review_ratings)?;
+    write_csv(&mut zip, "review_image.csv", &data.review_images)?;
+    write_csv(&mut zip, "shelf_entry.csv", &data.shelf_entries)?;
+    write_csv(&mut zip, "sauce_list.csv", &data.sauce_lists)?;
+    write_csv(&mut zip, "sauce_list_entry.csv", &data.sauce_list_entries)?;
+    write_csv(
+        &mut zip,
+        "tasting_session.csv",
+        &data.hosted_tasting_sessions,
+    )?;
+    write_csv(
+        &mut zip,
+        "tasting_participant.csv",
+        &data.tasting_participations,
+    )?;
+    write_csv(&mut zip, "tasting_scorecard.csv", &data.tasting_scorecards)?;
+    write_csv(
+        &mut zip,
+        "tasting_scorecard_rating.csv",
+        &data.tasting_scorecard_ratings,
+    )?;
+    write_csv(&mut zip, "follow.csv", &data.follows)?;
+    write_csv(&mut zip, "activity.csv", &data.activities)?;
+    write_csv(&mut zip, "review_comment.csv", &data.review_comments)?;
+    write_csv(&mut zip, "review_vote.csv", &data.review_votes)?;
+    write_csv(&mut zip, "report.csv", &data.reports)?;
+    write_csv(&mut zip, "moderation_action.csv", &data.moderation_actions)?;
+    write_csv(&mut zip, "user_block.csv", &data.blocks)?;
+    write_csv(&mut zip, "catalog_image.csv", &data.catalog_images)?;
+    write_csv(&mut zip, "sauce_barcode.csv", &data.sauce_barcodes)?;
+    write_csv(&mut zip, "sauce_batch.csv", &data.sauce_batches)?;
+    write_csv(&mut zip, "price_observation.csv", &data.price_observations)?;
+
+    Ok(zip.finish()?.into_inner())
+}
+
+fn archive_response(user_id: i32, archive: Vec<u8>) -> Response {
+    let filename = format!(
+        "hotsauce-export-{user_id}-{}.zip",
+        Utc::now().format("%Y-%m-%d")
+    );
+
+    (
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+        ],
+        archive,
+    )
+        .into_response()
+}
+
+/// Delete all of a user's background exports, handing back the paths of their archives to be
+/// removed with [`remove_export_archives`] once the deletion has been committed
+pub async fn delete_user_exports(
+    conn: &impl ConnectionTrait,
+    settings: &ExportSettings,
+    user_id: i32,
+) -> Result<Vec<PathBuf>> {
+    let exports = DataExport::find()
+        .filter(entity::data_export::Column::User.eq(user_id))
+        .all(conn)
+        .await?;
+
+    DataExport::delete_many()
+        .filter(entity::data_export::Column::User.eq(user_id))
+        .exec(conn)
+        .await?;
+
+    Ok(exports
+        .iter()
+        .map(|export| settings.archive_path(export.id))
+        .collect())
+}
+
+/// Remove export archives from disk. Like stored files, failures are only logged since nothing
+/// refers to the archives any more.
+pub async fn remove_export_archives(paths: &[PathBuf]) {
+    for path in paths {
+        match tokio::fs::remove_file(path).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                log::error!("Failed to delete export archive {}: {e:?}", path.display())
+            }
+            _ => (),
+        }
+    }
+}
+
+async fn generate_export(
+    conn: &DatabaseConnection,
+    settings: &ExportSettings,
+    export: &entity::data_export::Model,
+) -> Result<()> {
+    let archive = build_archive(&collect_user_data(conn, export.user).await?)?;
+    tokio::fs::write(settings.archive_path(export.id), archive)
+        .await
+        .map_err(anyhow::Error::from)?;
+
+    Ok(())
+}
+
+fn spawn_export(
+    conn: DatabaseConnection,
+    settings: ExportSettings,
+    export: entity::data_export::Model,
+) {
+    tokio::spawn(async move {
+        let status = match generate_export(&conn, &settings, &export).await {
+            Ok(()) => ExportStatus::Ready,
+            Err(e) => {
+                log::error!("Failed to generate data export {}: {e:?}", export.id);
+                ExportStatus::Failed
+            }
+        };
+
+        let mut export: entity::data_export::ActiveModel = export.into();
+        export.status = Set(status);
+        export.completed_at = Set(Some(Utc::now()));
+        if let Err(e) = export.update(&conn).await {
+            log::error!("Failed to update data export status: {e:?}");
+        }
+    });
+}
+
+#[derive(Debug, Serialize)]
+struct ExportResponse {
+    #[serde(flatten)]
+    export: entity::data_export::Model,
+    download_url: Option<String>,
+}
+
+impl From<entity::data_export::Model> for ExportResponse {
+    fn from(export: entity::data_export::Model) -> Self {
+        let download_url = (export.status == ExportStatus::Ready)
+            .then(|| format!("{API_PREFIX}/me/export/{}/download", export.id));
+
+        Self {
+            export,
+            download_url,
+        }
+    }
+}
+
+/// Small accounts get their archive straight away. Anything larger is built in the background, and
+/// the caller gets back an export they can poll until it's ready to download.
+async fn request_export(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(settings): Extension<ExportSettings>,
+) -> Result<Response> {
+    use entity::data_export::Column;
+
+    let review_count = Review::find()
+        .filter(entity::review::Column::User.eq(auth.user_id))
+        .count(conn)
+        .await?;
+
+    if review_count <= SYNC_EXPORT_MAX_REVIEWS {
+        let archive = build_archive(&collect_user_data(conn, auth.user_id).await?)?;
+        return Ok(archive_response(auth.user_id, archive));
+    }
+
+    if let Some(pending) = DataExport::find()
+        .filter(Column::User.eq(auth.user_id))
+        .filter(Column::Status.eq(ExportStatus::Pending))
+        .one(conn)
+        .await?
+    {
+        return Ok((StatusCode::ACCEPTED, Json(ExportResponse::from(pending))).into_response());
+    }
+
+    // Only the latest export is kept around
+    let old_archives = delete_user_exports(conn, &settings, auth.user_id).await?;
+    remove_export_archives(&old_archives).await;
+
+    let export = entity::data_export::ActiveModel {
+        user: Set(auth.user_id),
+        status: Set(ExportStatus::Pending),
+        requested_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await?;
+
+    spawn_export(conn.clone(), settings, export.clone());
+
+    Ok((StatusCode::ACCEPTED, Json(ExportResponse::from(export))).into_response())
+}
+
+async fn find_own_export(
+    conn: &DatabaseConnection,
+    auth: &AuthenticatedUser,
+    export_id: i32,
+) -> Result<entity::data_export::Model> {
+    DataExport::find_by_id(export_id)
+        .filter(entity::data_export::Column::User.eq(auth.user_id))
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)
+}
+
+async fn export_status(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(export_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let export = find_own_export(conn, &auth, export_id).await?;
+    Ok(Json(ExportResponse::from(export)))
+}
+
+async fn export_download(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(settings): Extension<ExportSettings>,
+    Path(export_id): Path<i32>,
+) -> Result<Response> {
+    let export = find_own_export(conn, &auth, export_id).await?;
+    if export.status != ExportStatus::Ready {
+        return Err(Error::NotFound);
+    }
+
+    let archive = tokio::fs::read(settings.archive_path(export.id))
+        .await
+        .map_err(anyhow::Error::from)?;
+
+    Ok(archive_response(auth.user_id, archive))
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/me/export", get(request_export))
+        .route("/me/export/:export_id", get(export_status))
+        .route("/me/export/:export_id/download", get(export_download))
+}
@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+
+use axum::{extract::Query, response::IntoResponse, routing::get, Extension, Json, Router};
+use entity::{
+    prelude::*,
+    sea_orm_active_enums::{ActivityKind, ProfilePrivacy},
+};
+use sea_orm::{prelude::*, sea_query::Query as SubQuery, Condition, QueryOrder, QuerySelect};
+use serde::Serialize;
+
+use crate::{
+    auth::AuthenticatedUser,
+    block::ignored_users,
+    error::Result,
+    pagination::{CursorPage, CursorParams},
+    review::{review_responses, ReviewResponse},
+};
+
+#[derive(Debug, Serialize)]
+struct FeedItem {
+    #[serde(flatten)]
+    activity: entity::activity::Model,
+    actor_username: Option<String>,
+    review_details: Option<ReviewResponse>,
+    sauce_details: Option<entity::sauce::Model>,
+    manufacturer_details: Option<entity::manufacturer::Model>,
+    list_details: Option<entity::sauce_list::Model>,
+}
+
+/// Look up everything the activities point at so each feed item can be shown on its own
+async fn feed_items(
+    conn: &DatabaseConnection,
+    activities: Vec<entity::activity::Model>,
+) -> Result<Vec<FeedItem>> {
+    let usernames: HashMap<i32, String> = User::find()
+        .filter(entity::user::Column::Id.is_in(activities.iter().filter_map(|a| a.actor)))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user.username))
+        .collect();
+
+    let reviews = Review::find()
+        .filter(entity::review::Column::Id.is_in(activities.iter().filter_map(|a| a.review)))
+        .all(conn)
+        .await?;
+    let mut reviews: HashMap<i32, ReviewResponse> = review_responses(conn, reviews)
+        .await?
+        .into_iter()
+        .map(|review| (review.review.id, review))
+        .collect();
+
+    let sauces: HashMap<i32, entity::sauce::Model> = Sauce::find()
+        .filter(entity::sauce::Column::Id.is_in(activities.iter().filter_map(|a| a.sauce)))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|sauce| (sauce.id, sauce))
+        .collect();
+
+    let manufacturers: HashMap<i32, entity::manufacturer::Model> = Manufacturer::find()
+        .filter(
+            entity::manufacturer::Column::Id
+                .is_in(activities.iter().filter_map(|a| a.manufacturer)),
+        )
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|manufacturer| (manufacturer.id, manufacturer))
+        .collect();
+
+    let lists: HashMap<i32, entity::sauce_list::Model> = SauceList::find()
+        .filter(
+            entity::sauce_list::Column::Id.is_in(activities.iter().filter_map(|a| a.sauce_list)),
+        )
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|list| (list.id, list))
+        .collect();
+
+    Ok(activities
+        .into_iter()
+        .map(|activity| FeedItem {
+            actor_username: activity.actor.and_then(|id| usernames.get(&id).cloned()),
+            review_details: activity.review.and_then(|id| reviews.remove(&id)),
+            sauce_details: activity.sauce.and_then(|id| sauces.get(&id).cloned()),
+            manufacturer_details: activity
+                .manufacturer
+                .and_then(|id| manufacturers.get(&id).cloned()),
+            list_details: activity.sauce_list.and_then(|id| lists.get(&id).cloned()),
+            activity,
+        })
+        .collect())
+}
+
+/// Newest first: everything done by users the caller follows, plus every addition to the catalog
+async fn feed(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Query(params): Query<CursorParams>,
+) -> Result<impl IntoResponse> {
+    use entity::activity::Column;
+
+    let followed = SubQuery::select()
+        .column(entity::follow::Column::Followee)
+        .from(Follow)
+        .and_where(entity::follow::Column::Follower.eq(auth.user_id))
+        .to_owned();
+
+    // Users who have since made their profile private, or been hidden by a moderator, drop out
+    // of their followers' feeds
+    let private_users = SubQuery::select()
+        .column(entity::user::Column::Id)
+        .from(User)
+        .cond_where(
+            Condition::any()
+                .add(entity::user::Column::Privacy.eq(ProfilePrivacy::Private))
+                .add(entity::user::Column::HiddenAt.is_not_null()),
+        )
+        .to_owned();
+
+    let public_lists = SubQuery::select()
+        .column(entity::sauce_list::Column::Id)
+        .from(SauceList)
+        .and_where(entity::sauce_list::Column::Public.eq(true))
+        .to_owned();
+
+    let hidden_reviews = SubQuery::select()
+        .column(entity::review::Column::Id)
+        .from(Review)
+        .and_where(entity::review::Column::HiddenAt.is_not_null())
+        .to_owned();
+    let hidden_sauces = SubQuery::select()
+        .column(entity::sauce::Column::Id)
+        .from(Sauce)
+        .and_where(entity::sauce::Column::HiddenAt.is_not_null())
+        .to_owned();
+    let hidden_manufacturers = SubQuery::select()
+        .column(entity::manufacturer::Column::Id)
+        .from(Manufacturer)
+        .and_where(entity::manufacturer::Column::HiddenAt.is_not_null())
+        .to_owned();
+
+    let mut condition = Condition::all()
+        .add(
+            Condition::any()
+                .add(Column::Actor.is_null())
+                .add(Column::Actor.not_in_subquery(ignored_users(auth.user_id))),
+        )
+        .add(
+            Condition::any()
+                .add(
+                    Condition::all()
+                        .add(Column::Actor.in_subquery(followed))
+                        .add(Column::Actor.not_in_subquery(private_users)),
+                )
+                .add(
+                    Column::Kind.is_in([ActivityKind::SauceAdded, ActivityKind::ManufacturerAdded]),
+                ),
+        )
+        .add(
+            Condition::any()
+                .add(Column::SauceList.is_null())
+                .add(Column::SauceList.in_subquery(public_lists)),
+        )
+        .add(
+            Condition::any()
+                .add(Column::Review.is_null())
+                .add(Column::Review.not_in_subquery(hidden_reviews)),
+        )
+        .add(
+            Condition::any()
+                .add(Column::Sauce.is_null())
+                .add(Column::Sauce.not_in_subquery(hidden_sauces)),
+        )
+        .add(
+            Condition::any()
+                .add(Column::Manufacturer.is_null())
+                .add(Column::Manufacturer.not_in_subquery(hidden_manufacturers)),
+        );
+
+    if let Some(before) = params.before {
+        condition = condition.add(Column::Id.lt(before));
+    }
+
+    // Everything goes into a single condition, as sea-query ORs any further filters into a
+    // top-level `Condition::any`
+    let select = Activity::find().filter(condition).order_by_desc(Column::Id);
+
+    // Fetch one extra to find out whether there's another page after this one
+    let limit = params.limit();
+    let mut activities = select.limit(limit as u64 + 1).all(conn).await?;
+    let next_cursor = if activities.len() > limit {
+        activities.truncate(limit);
+        activities.last().map(|activity| activity.id)
+    } else {
+        None
+    };
+
+    Ok(Json(CursorPage {
+        items: feed_items(conn, activities).await?,
+        next_cursor,
+    }))
+}
+
+pub fn router() -> Router {
+    Router::new().route("/feed", get(feed))
+}
@@ -0,0 +1,145 @@
+use axum::{
+    extract::Path,
+    response::IntoResponse,
+    routing::{get, put},
+    Extension, Json, Router,
+};
+use chrono::{DateTime, Utc};
+use entity::prelude::*;
+use sea_orm::{prelude::*, QueryOrder, Set};
+use serde::Serialize;
+
+use crate::{
+    auth::AuthenticatedUser,
+    block::{ensure_not_blocked, ignored_users},
+    error::{Error, Result},
+    profile::{ensure_profile_visible, find_user_by_username},
+};
+
+#[derive(Debug, Serialize)]
+struct FollowResponse {
+    id: i32,
+    username: String,
+    since: DateTime<Utc>,
+}
+
+async fn follow(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(username): Path<String>,
+) -> Result<impl IntoResponse> {
+    let user = find_user_by_username(conn, &username).await?;
+    if user.id == auth.user_id {
+        return Err(Error::BadRequest("You can't follow yourself".to_string()));
+    }
+    ensure_profile_visible(Some(&auth), &user)?;
+    ensure_not_blocked(conn, auth.user_id, user.id).await?;
+
+    let follow = match Follow::find_by_id((auth.user_id, user.id))
+        .one(conn)
+        .await?
+    {
+        Some(existing) => existing,
+        None => {
+            entity::follow::ActiveModel {
+                follower: Set(auth.user_id),
+                followee: Set(user.id),
+                created_at: Set(Utc::now()),
+            }
+            .insert(conn)
+            .await?
+        }
+    };
+
+    Ok(Json(FollowResponse {
+        id: user.id,
+        username: user.username,
+        since: follow.created_at,
+    }))
+}
+
+async fn unfollow(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(username): Path<String>,
+) -> Result<impl IntoResponse> {
+    let user = find_user_by_username(conn, &username).await?;
+    let result = Follow::delete_by_id((auth.user_id, user.id))
+        .exec(conn)
+        .await?;
+
+    if result.rows_affected == 0 {
+        return Err(Error::NotFound);
+    }
+
+    Ok(Json(()))
+}
+
+/// Pair each follow with the user on the other end of it
+async fn follow_responses(
+    conn: &DatabaseConnection,
+    follows: Vec<entity::follow::Model>,
+    other_user: impl Fn(&entity::follow::Model) -> i32,
+) -> Result<Vec<FollowResponse>> {
+    let users = User::find()
+        .filter(entity::user::Column::Id.is_in(follows.iter().map(&other_user)))
+        .all(conn)
+        .await?;
+
+    Ok(follows
+        .iter()
+        .filter_map(|follow| {
+            let user = users.iter().find(|user| user.id == other_user(follow))?;
+            Some(FollowResponse {
+                id: user.id,
+                username: user.username.clone(),
+                since: follow.created_at,
+            })
+        })
+        .collect())
+}
+
+/// Muted users are still listed here so they can be unfollowed, and blocking someone already ends
+/// any follow between the two of them
+async fn following(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+) -> Result<impl IntoResponse> {
+    use entity::follow::Column;
+
+    let follows = Follow::find()
+        .filter(Column::Follower.eq(auth.user_id))
+        .order_by_desc(Column::CreatedAt)
+        .all(conn)
+        .await?;
+
+    Ok(Json(
+        follow_responses(conn, follows, |follow| follow.followee).await?,
+    ))
+}
+
+/// Anyone the caller has muted is left out
+async fn followers(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+) -> Result<impl IntoResponse> {
+    use entity::follow::Column;
+
+    let follows = Follow::find()
+        .filter(Column::Followee.eq(auth.user_id))
+        .filter(Column::Follower.not_in_subquery(ignored_users(auth.user_id)))
+        .order_by_desc(Column::CreatedAt)
+        .all(conn)
+        .await?;
+
+    Ok(Json(
+        follow_responses(conn, follows, |follow| follow.follower).await?,
+    ))
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/user/:username/follow", put(follow).delete(unfollow))
+        .route("/following", get(following))
+        .route("/followers", get(followers))
+}
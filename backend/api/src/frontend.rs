@@ -0,0 +1,175 @@
+use std::path::{Component, Path, PathBuf};
+
+use axum::{
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    Extension,
+};
+
+use crate::{error::Error, API_PREFIX};
+
+/// Vite puts everything with a content hash in its name in here, so those files never change
+const HASHED_ASSETS_DIR: &str = "assets";
+
+const HASHED_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+/// Everything else, `index.html` especially, has to be checked so a new build gets picked up
+const UNHASHED_CACHE_CONTROL: &str = "no-cache";
+
+/// Compressed copies of files that may have been built alongside them, as the encoding and the
+/// extension added to the file name, best first
+const ENCODINGS: [(&str, &str); 2] = [("br", "br"), ("gzip", "gz")];
+
+/// The built frontend, served from disk for any path the API doesn't handle
+#[derive(Debug, Clone)]
+pub struct FrontendFiles {
+    pub dir: PathBuf,
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("js" | "mjs") => "text/javascript; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("json" | "map") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("ico") => "image/x-icon",
+        Some("woff") => "font/woff",
+        Some("woff2") => "font/woff2",
+        Some("txt") => "text/plain; charset=utf-8",
+        Some("wasm") => "application/wasm",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Turn a request path into one relative to the frontend directory, refusing anything that could
+/// reach outside of it
+fn relative_path(uri: &Uri) -> Option<PathBuf> {
+    let path = Path::new(uri.path().trim_start_matches('/'));
+    if path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        Some(path.to_path_buf())
+    } else {
+        None
+    }
+}
+
+fn accepts_encoding(headers: &HeaderMap, encoding: &str) -> bool {
+    headers
+        .get_all(header::ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|coding| {
+            let mut parts = coding.split(';');
+            let name = parts.next()?.trim();
+            // `;q=0` means the client specifically doesn't want this one
+            let refused = parts.any(|param| {
+                param
+                    .trim()
+                    .strip_prefix("q=")
+                    .and_then(|q| q.parse::<f32>().ok())
+                    == Some(0.0)
+            });
+            (!refused).then_some(name)
+        })
+        .any(|name| name == encoding)
+}
+
+async fn is_file(path: &Path) -> bool {
+    tokio::fs::metadata(path)
+        .await
+        .map(|metadata| metadata.is_file())
+        .unwrap_or(false)
+}
+
+/// Read a file, or a precompressed copy of it if the client can take one
+async fn serve_file(path: &Path, relative: &Path, headers: &HeaderMap) -> Response {
+    let mut file = (path.to_path_buf(), None);
+    for (encoding, extension) in ENCODINGS {
+        let mut compressed = path.as_os_str().to_owned();
+        compressed.push(".");
+        compressed.push(extension);
+        let compressed = PathBuf::from(compressed);
+        if accepts_encoding(headers, encoding) && is_file(&compressed).await {
+            file = (compressed, Some(encoding));
+            break;
+        }
+    }
+
+    let (file_path, encoding) = file;
+    let data = match tokio::fs::read(&file_path).await {
+        Ok(data) => data,
+        Err(e) => {
+            log::error!("Couldn't read {}: {e}", file_path.display());
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let cache_control = match relative.starts_with(HASHED_ASSETS_DIR) {
+        true => HASHED_CACHE_CONTROL,
+        false => UNHASHED_CACHE_CONTROL,
+    };
+
+    let mut response = (
+        [
+            (header::CONTENT_TYPE, content_type(path)),
+            (header::CACHE_CONTROL, cache_control),
+            (header::VARY, "accept-encoding"),
+        ],
+        data,
+    )
+        .into_response();
+    if let Some(encoding) = encoding {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_ENCODING, HeaderValue::from_static(encoding));
+    }
+
+    response
+}
+
+/// Serve a file from the built frontend, or `index.html` for any other page so the app's router
+/// can deal with it
+pub async fn serve_frontend(
+    Extension(files): Extension<FrontendFiles>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+) -> Response {
+    if method != Method::GET && method != Method::HEAD {
+        return StatusCode::METHOD_NOT_ALLOWED.into_response();
+    }
+
+    // Unknown API routes shouldn't get the app back
+    if uri.path().starts_with(API_PREFIX) {
+        return Error::NotFound.into_response();
+    }
+
+    let relative = match relative_path(&uri) {
+        Some(relative) => relative,
+        None => return StatusCode::NOT_FOUND.into_response(),
+    };
+    let path = files.dir.join(&relative);
+    if !relative.as_os_str().is_empty() && is_file(&path).await {
+        return serve_file(&path, &relative, &headers).await;
+    }
+
+    // Anything that looks like a file should have been found above, so only fall back to the app
+    // for page routes
+    if relative.extension().is_some() {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    let index = Path::new("index.html");
+    let index_path = files.dir.join(index);
+    if !is_file(&index_path).await {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    serve_file(&index_path, index, &headers).await
+}
@@ -0,0 +1,191 @@
+//! Turning uploaded images into files at standard sizes that are safe to serve.
+//!
+//! Uploads are decoded and re-encoded from scratch, which leaves behind any metadata the original
+//! carried (EXIF location data in particular). The EXIF orientation is applied to the pixels first
+//! so phone photos don't end up on their side.
+
+use std::io::Cursor;
+
+use axum::extract::multipart::Field;
+use image::{
+    imageops::FilterType,
+    io::{Limits, Reader},
+    DynamicImage, ImageFormat,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+/// Uploads claiming to be bigger than this are refused before decoding
+const MAX_DECODED_DIMENSION: u32 = 12_000;
+const MAX_DECODE_ALLOC: u64 = 512 * 1024 * 1024;
+
+/// libwebp's quality setting, from 0 to 100
+const WEBP_QUALITY: f32 = 80.0;
+
+/// Every stored image is a lossy WebP, with its alpha channel kept if it has one
+pub const CONTENT_TYPE: &str = "image/webp";
+
+/// The standard sizes images are stored at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageSize {
+    Thumbnail,
+    Medium,
+    Full,
+}
+
+impl ImageSize {
+    pub const ALL: [ImageSize; 3] = [ImageSize::Thumbnail, ImageSize::Medium, ImageSize::Full];
+
+    /// Longest side of an image at this size. Smaller images are never scaled up.
+    fn max_dimension(self) -> u32 {
+        match self {
+            ImageSize::Thumbnail => 320,
+            ImageSize::Medium => 800,
+            ImageSize::Full => 1600,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ImageSize::Thumbnail => "thumbnail",
+            ImageSize::Medium => "medium",
+            ImageSize::Full => "full",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ImageSettings {
+    /// Largest upload accepted, in bytes
+    pub max_upload_bytes: usize,
+}
+
+#[derive(Debug)]
+pub struct EncodedImage {
+    pub data: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Work out the format from the file's contents, whatever the upload claimed it was
+fn sniff_format(data: &[u8]) -> Result<ImageFormat> {
+    match image::guess_format(data) {
+        Ok(format @ (ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP)) => Ok(format),
+        _ => Err(Error::BadRequest(
+            "Images must be JPEG, PNG or WebP".to_string(),
+        )),
+    }
+}
+
+/// The EXIF orientation tag, 1 (upright) if there isn't one
+fn exif_orientation(data: &[u8]) -> u32 {
+    exif::Reader::new()
+        .read_from_container(&mut Cursor::new(data))
+        .ok()
+        .and_then(|exif| {
+            exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)
+                .and_then(|field| field.value.get_uint(0))
+        })
+        .unwrap_or(1)
+}
+
+fn apply_orientation(image: DynamicImage, orientation: u32) -> DynamicImage {
+    match orientation {
+        2 => image.fliph(),
+        3 => image.rotate180(),
+        4 => image.flipv(),
+        5 => image.rotate90().fliph(),
+        6 => image.rotate90(),
+        7 => image.rotate270().fliph(),
+        8 => image.rotate270(),
+        _ => image,
+    }
+}
+
+fn encode(image: &DynamicImage) -> Result<EncodedImage> {
+    let (width, height) = (image.width(), image.height());
+    let encoded = match image.color().has_alpha() {
+        true => {
+            let pixels = image.to_rgba8();
+            webp::Encoder::from_rgba(&pixels, width, height).encode_simple(false, WEBP_QUALITY)
+        }
+        false => {
+            let pixels = image.to_rgb8();
+            webp::Encoder::from_rgb(&pixels, width, height).encode_simple(false, WEBP_QUALITY)
+        }
+    };
+    let data = encoded.map_err(|e| anyhow::anyhow!("Couldn't encode the image as WebP: {e:?}"))?;
+
+    Ok(EncodedImage {
+        data: data.to_vec(),
+        width,
+        height,
+    })
+}
+
+fn decode(data: &[u8]) -> Result<DynamicImage> {
+    let format = sniff_format(data)?;
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DECODED_DIMENSION);
+    limits.max_image_height = Some(MAX_DECODED_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODE_ALLOC);
+
+    let mut reader = Reader::with_format(Cursor::new(data), format);
+    reader.limits(limits);
+
+    let image = reader
+        .decode()
+        .map_err(|_| Error::BadRequest("The image couldn't be read".to_string()))?;
+
+    Ok(match format {
+        ImageFormat::Jpeg | ImageFormat::WebP => apply_orientation(image, exif_orientation(data)),
+        _ => image,
+    })
+}
+
+/// Decode an uploaded image and encode it at each of the requested sizes, in the same order. This
+/// is CPU heavy, so run it off the async runtime.
+pub fn process_image<const N: usize>(
+    data: &[u8],
+    sizes: [ImageSize; N],
+) -> Result<[EncodedImage; N]> {
+    let mut image = decode(data)?;
+
+    // Work down from the largest size, so each resize starts from the smallest image it can
+    let mut order: [usize; N] = std::array::from_fn(|i| i);
+    order.sort_by_key(|&i| std::cmp::Reverse(sizes[i].max_dimension()));
+
+    let mut encoded: [Option<EncodedImage>; N] = std::array::from_fn(|_| None);
+    for i in order {
+        let max = sizes[i].max_dimension();
+        if image.width().max(image.height()) > max {
+            image = image.resize(max, max, FilterType::Lanczos3);
+        }
+        encoded[i] = Some(encode(&image)?);
+    }
+
+    Ok(encoded.map(|image| image.expect("every size is encoded")))
+}
+
+/// Read an uploaded file, giving up as soon as it goes over the size limit
+pub async fn read_upload(mut field: Field<'_>, max_bytes: usize) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    while let Some(chunk) = field
+        .chunk()
+        .await
+        .map_err(|e| Error::BadRequest(e.to_string()))?
+    {
+        if data.len() + chunk.len() > max_bytes {
+            return Err(Error::BadRequest(format!(
+                "Images can be at most {} MB",
+                max_bytes / (1024 * 1024)
+            )));
+        }
+        data.extend_from_slice(&chunk);
+    }
+
+    Ok(data)
+}
@@ -1,8 +1,9 @@
-use std::{net::SocketAddr, time::Duration};
+use std::{net::SocketAddr, path::Path, sync::Arc, time::Duration};
 
 use axum::{
     body::Body,
     error_handling::HandleErrorLayer,
+    handler::Handler,
     http::{Request, StatusCode},
     BoxError, Extension, Router,
 };
@@ -19,11 +20,57 @@ use tower_http::{
 };
 use tracing_subscriber::{prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt};
 
+mod account;
+mod activity;
 mod auth;
+mod badge;
+mod barcode;
+mod batch;
+mod block;
+mod catalog_image;
+mod comment;
+mod compare;
 mod error;
+mod export;
+mod feed;
+mod follow;
+mod frontend;
+mod images;
 mod manufacturer;
+mod markup;
+mod moderation;
+mod pages;
+mod pagination;
+mod price;
 mod profile;
+mod radar;
+mod ratings;
+mod recommendation;
+mod review;
+mod review_image;
 mod sauce;
+mod sauce_list;
+mod screening;
+mod shelf;
+mod storage;
+mod taste_profile;
+mod tasting;
+mod tasting_events;
+
+/// Path that all api routes are nested under
+pub const API_PREFIX: &str = "/api/v1";
+
+/// How long a request can take before it's cut off
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Uploads can be several large images that all need resizing, so they get longer
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone)]
+pub struct SiteSettings {
+    /// Address the site is publicly served from, without a trailing slash, for links that are
+    /// used outside of it
+    pub public_url: String,
+}
 
 async fn handle_timeout_error(err: BoxError) -> (StatusCode, String) {
     if err.is::<tower::timeout::error::Elapsed>() {
@@ -41,16 +88,55 @@ async fn handle_timeout_error(err: BoxError) -> (StatusCode, String) {
 
 fn api_router() -> Router {
     Router::new()
+        .merge(account::router())
         .merge(auth::router())
+        .merge(badge::router())
+        .merge(barcode::router())
+        .merge(batch::router())
+        .merge(block::router())
+        .merge(catalog_image::router())
+        .merge(comment::router())
+        .merge(compare::router())
+        .merge(export::router())
+        .merge(feed::router())
+        .merge(follow::router())
         .merge(manufacturer::router())
+        .merge(moderation::router())
+        .merge(price::router())
         .merge(profile::router())
+        .merge(radar::router())
+        .merge(recommendation::router())
+        .merge(review::router())
+        .merge(review_image::router())
         .merge(sauce::router())
+        .merge(sauce_list::router())
+        .merge(shelf::router())
+        .merge(taste_profile::router())
+        .merge(tasting::router())
+        .merge(tasting_events::router())
+}
+
+/// Routes that take file uploads
+fn upload_router() -> Router {
+    Router::new()
+        .merge(catalog_image::upload_router())
+        .merge(review_image::upload_router())
 }
 
 #[derive(Debug, Deserialize)]
 struct AppConfig {
     database_uri: String,
     private_cookie_key: Option<String>,
+    recommendation_refresh_secs: Option<u64>,
+    export_dir: Option<String>,
+    image_dir: Option<String>,
+    max_image_upload_bytes: Option<usize>,
+    public_url: Option<String>,
+    frontend_dir: Option<String>,
+    #[serde(default)]
+    serve_frontend: bool,
+    #[serde(default)]
+    screening: screening::ScreeningConfig,
 }
 
 fn private_cookie_key(config: &AppConfig) -> anyhow::Result<PrivateCookieKey> {
@@ -97,14 +183,71 @@ async fn main() -> anyhow::Result<()> {
 
     let cookie_key = private_cookie_key(&config)?;
 
+    let tasting_events = tasting_events::TastingEvents::default();
+    let recommendations = recommendation::SharedSimilarityModel::default();
+    recommendation::spawn_refresh_task(
+        db.clone(),
+        recommendations.clone(),
+        Duration::from_secs(config.recommendation_refresh_secs.unwrap_or(600)),
+    );
+
+    let export_settings = export::ExportSettings {
+        dir: config.export_dir.as_deref().unwrap_or("exports").into(),
+    };
+    std::fs::create_dir_all(&export_settings.dir)?;
+
+    let storage: storage::SharedStorage = Arc::new(storage::LocalStorage::new(
+        config.image_dir.as_deref().unwrap_or("images"),
+    )?);
+    let image_settings = images::ImageSettings {
+        max_upload_bytes: config.max_image_upload_bytes.unwrap_or(10 * 1024 * 1024),
+    };
+
+    let screening = screening::Screening::from_config(&config.screening);
+
+    let site_settings = SiteSettings {
+        public_url: config
+            .public_url
+            .as_deref()
+            .unwrap_or("http://localhost:3030")
+            .trim_end_matches('/')
+            .to_string(),
+    };
+    let frontend_dir = Path::new(config.frontend_dir.as_deref().unwrap_or("../frontend/dist"));
+    let app_shell = pages::AppShell::new(frontend_dir);
+    let frontend_files = frontend::FrontendFiles {
+        dir: frontend_dir.to_path_buf(),
+    };
+
     let x_request_id = HeaderName::from_static("x-request-id");
 
-    let app = Router::new().nest("/api/v1", api_router()).layer(
+    let mut app = Router::new()
+        .nest(API_PREFIX, api_router())
+        .merge(pages::router());
+    if config.serve_frontend {
+        log::info!("Serving the frontend from {}", frontend_dir.display());
+        app = app.fallback(frontend::serve_frontend.into_service());
+    }
+
+    let app = app
+        .layer(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(handle_timeout_error))
+                .timeout(REQUEST_TIMEOUT),
+        )
+        .nest(
+            API_PREFIX,
+            upload_router().layer(
+                ServiceBuilder::new()
+                    .layer(HandleErrorLayer::new(handle_timeout_error))
+                    .timeout(UPLOAD_TIMEOUT),
+            ),
+        );
+
+    let app = app.layer(
         ServiceBuilder::new()
             .set_x_request_id(MakeRequestUuid)
             .layer(PropagateRequestIdLayer::new(x_request_id.clone()))
-            .layer(HandleErrorLayer::new(handle_timeout_error))
-            .timeout(Duration::from_secs(10))
             .layer(
                 TraceLayer::new_for_http().make_span_with(move |req: &Request<Body>| {
                     tracing::debug_span!(
@@ -116,7 +259,16 @@ async fn main() -> anyhow::Result<()> {
                 }),
             )
             .layer(Extension(db))
-            .layer(Extension(cookie_key)),
+            .layer(Extension(cookie_key))
+            .layer(Extension(recommendations))
+            .layer(Extension(tasting_events))
+            .layer(Extension(export_settings))
+            .layer(Extension(storage))
+            .layer(Extension(image_settings))
+            .layer(Extension(screening))
+            .layer(Extension(site_settings))
+            .layer(Extension(app_shell))
+            .layer(Extension(frontend_files)),
     );
 
     let addr = SocketAddr::from(([127, 0, 0, 1], 3030));
@@ -2,18 +2,44 @@ use axum::{response::IntoResponse, routing::get, Extension, Json, Router};
 use sea_orm::{prelude::*, Set};
 
 use entity::prelude::*;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::error::{Error, Result};
+use crate::{
+    activity::{record_activity, NewActivity},
+    auth::AuthenticatedUser,
+    catalog_image::{manufacturer_covers, CatalogImageResponse},
+    error::Result,
+};
+
+#[derive(Debug, Serialize)]
+struct ManufacturerResponse {
+    #[serde(flatten)]
+    manufacturer: entity::manufacturer::Model,
+    image: Option<CatalogImageResponse>,
+}
 
 async fn manufacturer_list(
     Extension(ref conn): Extension<DatabaseConnection>,
 ) -> Result<impl IntoResponse> {
-    Manufacturer::find()
+    let manufacturers = Manufacturer::find()
+        .filter(entity::manufacturer::Column::HiddenAt.is_null())
         .all(conn)
-        .await
-        .map(|x| Json(x))
-        .map_err(Error::from)
+        .await?;
+    let manufacturer_ids = manufacturers
+        .iter()
+        .map(|manufacturer| manufacturer.id)
+        .collect();
+    let mut covers = manufacturer_covers(conn, manufacturer_ids).await?;
+
+    let responses: Vec<ManufacturerResponse> = manufacturers
+        .into_iter()
+        .map(|manufacturer| ManufacturerResponse {
+            image: covers.remove(&manufacturer.id),
+            manufacturer,
+        })
+        .collect();
+
+    Ok(Json(responses))
 }
 
 #[derive(Deserialize, Debug)]
@@ -22,6 +48,7 @@ struct NewManufacturer {
 }
 
 async fn manufacturer_insert(
+    auth: Option<AuthenticatedUser>,
     Extension(ref conn): Extension<DatabaseConnection>,
     Json(new): Json<NewManufacturer>,
 ) -> Result<impl IntoResponse> {
@@ -40,6 +67,15 @@ async fn manufacturer_insert(
     .insert(conn)
     .await?;
 
+    record_activity(
+        conn,
+        auth.map(|auth| auth.user_id),
+        NewActivity::ManufacturerAdded {
+            manufacturer: inserted.id,
+        },
+    )
+    .await?;
+
     Ok(Json(inserted))
 }
 
@@ -0,0 +1,627 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query},
+    response::IntoResponse,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use chrono::{DateTime, Utc};
+use entity::{
+    prelude::*,
+    sea_orm_active_enums::{
+        ActivityKind, ModerationActionKind, ModerationTarget, ReportReason, ReportStatus,
+    },
+};
+use sea_orm::{prelude::*, sea_query::Expr, ConnectionTrait, QueryOrder, Set, TransactionTrait};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::AuthenticatedUser,
+    catalog_image::catalog_image_keys,
+    comment::delete_comment,
+    error::{Error, Result},
+    pagination::{fetch_page, PageParams},
+    review::delete_reviews,
+    review_image::review_image_keys,
+    storage::{delete_stored_files, SharedStorage},
+    API_PREFIX,
+};
+
+const MAX_DETAILS_LENGTH: usize = 2000;
+
+/// Check that the caller is a moderator
+pub async fn find_moderator(
+    conn: &DatabaseConnection,
+    auth: &AuthenticatedUser,
+) -> Result<entity::user::Model> {
+    let user = User::find_by_id(auth.user_id)
+        .one(conn)
+        .await?
+        .ok_or(Error::Unauthorized)?;
+
+    match user.is_moderator {
+        true => Ok(user),
+        false => Err(Error::Forbidden),
+    }
+}
+
+/// Whether there's a viewer and they're a moderator, for things moderators see more of
+pub async fn is_moderator(
+    conn: &DatabaseConnection,
+    viewer: Option<&AuthenticatedUser>,
+) -> Result<bool> {
+    let viewer = match viewer {
+        Some(viewer) => viewer,
+        None => return Ok(false),
+    };
+
+    match find_moderator(conn, viewer).await {
+        Ok(_) => Ok(true),
+        Err(Error::Unauthorized | Error::Forbidden) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Whatever a report or moderation action points at
+#[derive(Debug, Serialize)]
+struct Target {
+    /// The user responsible for the content, who receives any warning about it
+    owner: Option<i32>,
+    owner_username: Option<String>,
+    preview: Option<String>,
+    hidden: bool,
+}
+
+/// The user whose activity added a catalog entry, if anyone was logged in at the time
+async fn catalog_owner(
+    conn: &impl ConnectionTrait,
+    kind: ActivityKind,
+    column: entity::activity::Column,
+    id: i32,
+) -> Result<Option<i32>> {
+    Ok(Activity::find()
+        .filter(entity::activity::Column::Kind.eq(kind))
+        .filter(column.eq(id))
+        .one(conn)
+        .await?
+        .and_then(|activity| activity.actor))
+}
+
+async fn find_target(
+    conn: &impl ConnectionTrait,
+    kind: ModerationTarget,
+    id: i32,
+) -> Result<Option<Target>> {
+    let target = match kind {
+        ModerationTarget::Review => Review::find_by_id(id)
+            .one(conn)
+            .await?
+            .map(|review| Target {
+                owner: Some(review.user),
+                owner_username: None,
+                preview: review.text,
+                hidden: review.hidden_at.is_some(),
+            }),
+        ModerationTarget::Comment => ReviewComment::find_by_id(id)
+            .one(conn)
+            .await?
+            .filter(|comment| comment.deleted_at.is_none())
+            .map(|comment| Target {
+                owner: Some(comment.author),
+                owner_username: None,
+                preview: Some(comment.body),
+                hidden: comment.hidden_at.is_some(),
+            }),
+        ModerationTarget::User => User::find_by_id(id).one(conn).await?.map(|user| Target {
+            owner: Some(user.id),
+            owner_username: None,
+            preview: Some(user.username),
+            hidden: user.hidden_at.is_some(),
+        }),
+        ModerationTarget::Sauce => match Sauce::find_by_id(id).one(conn).await? {
+            Some(sauce) => Some(Target {
+                owner: catalog_owner(
+                    conn,
+                    ActivityKind::SauceAdded,
+                    entity::activity::Column::Sauce,
+                    sauce.id,
+                )
+                .await?,
+                owner_username: None,
+                preview: Some(sauce.name),
+                hidden: sauce.hidden_at.is_some(),
+            }),
+            None => None,
+        },
+        ModerationTarget::Manufacturer => match Manufacturer::find_by_id(id).one(conn).await? {
+            Some(manufacturer) => Some(Target {
+                owner: catalog_owner(
+                    conn,
+                    ActivityKind::ManufacturerAdded,
+                    entity::activity::Column::Manufacturer,
+                    manufacturer.id,
+                )
+                .await?,
+                owner_username: None,
+                preview: Some(manufacturer.name),
+                hidden: manufacturer.hidden_at.is_some(),
+            }),
+            None => None,
+        },
+        ModerationTarget::CatalogImage => {
+            CatalogImage::find_by_id(id)
+                .one(conn)
+                .await?
+                .map(|image| Target {
+                    owner: image.uploaded_by,
+                    owner_username: None,
+                    preview: Some(format!("{API_PREFIX}/catalog_image/{}/thumbnail", image.id)),
+                    hidden: image.hidden_at.is_some(),
+                })
+        }
+    };
+
+    Ok(target)
+}
+
+/// Hide or unhide whatever a report points at
+async fn set_hidden(
+    conn: &impl ConnectionTrait,
+    kind: ModerationTarget,
+    id: i32,
+    hidden: bool,
+) -> Result<()> {
+    let now = Expr::value(hidden.then(Utc::now));
+
+    match kind {
+        ModerationTarget::Review => {
+            Review::update_many()
+                .col_expr(entity::review::Column::HiddenAt, now)
+                .filter(entity::review::Column::Id.eq(id))
+                .exec(conn)
+                .await?;
+        }
+        ModerationTarget::Comment => {
+            ReviewComment::update_many()
+                .col_expr(entity::review_comment::Column::HiddenAt, now)
+                .filter(entity::review_comment::Column::Id.eq(id))
+                .exec(conn)
+                .await?;
+        }
+        ModerationTarget::User => {
+            User::update_many()
+                .col_expr(entity::user::Column::HiddenAt, now)
+                .filter(entity::user::Column::Id.eq(id))
+                .exec(conn)
+                .await?;
+        }
+        ModerationTarget::Sauce => {
+            Sauce::update_many()
+                .col_expr(entity::sauce::Column::HiddenAt, now)
+                .filter(entity::sauce::Column::Id.eq(id))
+                .exec(conn)
+                .await?;
+        }
+        ModerationTarget::Manufacturer => {
+            Manufacturer::update_many()
+                .col_expr(entity::manufacturer::Column::HiddenAt, now)
+                .filter(entity::manufacturer::Column::Id.eq(id))
+                .exec(conn)
+                .await?;
+        }
+        ModerationTarget::CatalogImage => {
+            CatalogImage::update_many()
+                .col_expr(entity::catalog_image::Column::HiddenAt, now)
+                .filter(entity::catalog_image::Column::Id.eq(id))
+                .exec(conn)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn delete_target(conn: &impl ConnectionTrait, kind: ModerationTarget, id: i32) -> Result<()> {
+    match kind {
+        ModerationTarget::Review => delete_reviews(conn, vec![id]).await?,
+        ModerationTarget::Comment => {
+            if let Some(comment) = ReviewComment::find_by_id(id).one(conn).await? {
+                delete_comment(conn, comment).await?;
+            }
+        }
+        ModerationTarget::User => {
+            return Err(Error::BadRequest(
+                "Users can't be deleted by moderators, hide them instead".to_string(),
+            ));
+        }
+        ModerationTarget::Sauce => {
+            let reviewed = Review::find()
+                .filter(entity::review::Column::Sauce.eq(id))
+                .one(conn)
+                .await?
+                .is_some();
+            if reviewed {
+                return Err(Error::BadRequest(
+                    "Sauces that have been reviewed can't be deleted, hide them instead"
+                        .to_string(),
+                ));
+            }
+
+            Sauce::delete_by_id(id).exec(conn).await?;
+        }
+        ModerationTarget::Manufacturer => {
+            let has_sauces = Sauce::find()
+                .filter(entity::sauce::Column::Manufacturer.eq(id))
+                .one(conn)
+                .await?
+                .is_some();
+            if has_sauces {
+                return Err(Error::BadRequest(
+                    "Manufacturers with sauces can't be deleted, hide them instead".to_string(),
+                ));
+            }
+
+            Manufacturer::delete_by_id(id).exec(conn).await?;
+        }
+        ModerationTarget::CatalogImage => {
+            CatalogImage::delete_by_id(id).exec(conn).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Storage keys of the uploaded files that go when a target is deleted
+async fn stored_files(
+    conn: &impl ConnectionTrait,
+    kind: ModerationTarget,
+    id: i32,
+) -> Result<Vec<String>> {
+    use entity::catalog_image::Column;
+
+    match kind {
+        ModerationTarget::Review => review_image_keys(conn, vec![id]).await,
+        ModerationTarget::Sauce => catalog_image_keys(conn, Column::Sauce.eq(id)).await,
+        ModerationTarget::Manufacturer => {
+            catalog_image_keys(conn, Column::Manufacturer.eq(id)).await
+        }
+        ModerationTarget::CatalogImage => catalog_image_keys(conn, Column::Id.eq(id)).await,
+        ModerationTarget::Comment | ModerationTarget::User => Ok(Vec::new()),
+    }
+}
+
+/// Hide newly saved content that screening wasn't sure about, and put it in the moderation queue
+pub async fn hold_for_moderation(
+    conn: &impl ConnectionTrait,
+    target_kind: ModerationTarget,
+    target_id: i32,
+    details: &str,
+) -> Result<()> {
+    use entity::report::Column;
+
+    set_hidden(conn, target_kind, target_id, true).await?;
+
+    // Edits to content that's already waiting for a moderator don't need another report
+    let already_held = Report::find()
+        .filter(Column::TargetKind.eq(target_kind))
+        .filter(Column::TargetId.eq(target_id))
+        .filter(Column::Reason.eq(ReportReason::Held))
+        .filter(Column::Status.eq(ReportStatus::Open))
+        .one(conn)
+        .await?
+        .is_some();
+    if already_held {
+        return Ok(());
+    }
+
+    entity::report::ActiveModel {
+        reporter: Set(None),
+        target_kind: Set(target_kind),
+        target_id: Set(target_id),
+        reason: Set(ReportReason::Held),
+        details: Set(Some(details.to_string())),
+        status: Set(ReportStatus::Open),
+        created_at: Set(Utc::now()),
+        resolved_at: Set(None),
+        resolved_by: Set(None),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await?;
+
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+struct NewReport {
+    target_kind: ModerationTarget,
+    target_id: i32,
+    reason: ReportReason,
+    details: Option<String>,
+}
+
+async fn report_insert(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Json(new): Json<NewReport>,
+) -> Result<impl IntoResponse> {
+    use entity::report::Column;
+
+    if new
+        .details
+        .as_ref()
+        .is_some_and(|details| details.chars().count() > MAX_DETAILS_LENGTH)
+    {
+        return Err(Error::BadRequest(format!(
+            "Details must be at most {MAX_DETAILS_LENGTH} characters"
+        )));
+    }
+
+    if new.reason == ReportReason::Held {
+        return Err(Error::BadRequest(
+            "Only screening can hold content for moderation".to_string(),
+        ));
+    }
+
+    find_target(conn, new.target_kind, new.target_id)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    let already_reported = Report::find()
+        .filter(Column::Reporter.eq(auth.user_id))
+        .filter(Column::TargetKind.eq(new.target_kind))
+        .filter(Column::TargetId.eq(new.target_id))
+        .filter(Column::Status.eq(ReportStatus::Open))
+        .one(conn)
+        .await?
+        .is_some();
+    if already_reported {
+        return Err(Error::BadRequest(
+            "You've already reported this".to_string(),
+        ));
+    }
+
+    let report = entity::report::ActiveModel {
+        reporter: Set(Some(auth.user_id)),
+        target_kind: Set(new.target_kind),
+        target_id: Set(new.target_id),
+        reason: Set(new.reason),
+        details: Set(new.details),
+        status: Set(ReportStatus::Open),
+        created_at: Set(Utc::now()),
+        resolved_at: Set(None),
+        resolved_by: Set(None),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await?;
+
+    Ok(Json(report))
+}
+
+#[derive(Debug, Serialize)]
+struct ReportResponse {
+    #[serde(flatten)]
+    report: entity::report::Model,
+    reporter_username: Option<String>,
+
+    /// Missing once the reported content has been deleted
+    target: Option<Target>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReportQuery {
+    status: Option<ReportStatus>,
+}
+
+/// Reports with the given status, open ones by default, oldest first
+async fn report_queue(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Query(query): Query<ReportQuery>,
+    Query(page_params): Query<PageParams>,
+) -> Result<impl IntoResponse> {
+    use entity::report::Column;
+
+    find_moderator(conn, &auth).await?;
+
+    let select = Report::find()
+        .filter(Column::Status.eq(query.status.unwrap_or(ReportStatus::Open)))
+        .order_by_asc(Column::CreatedAt)
+        .order_by_asc(Column::Id);
+
+    let mut page = fetch_page(conn, select, &page_params).await?;
+    let reports = std::mem::take(&mut page.items);
+
+    let mut targets = Vec::with_capacity(reports.len());
+    for report in &reports {
+        targets.push(find_target(conn, report.target_kind, report.target_id).await?);
+    }
+
+    let user_ids = reports
+        .iter()
+        .filter_map(|report| report.reporter)
+        .chain(targets.iter().flatten().filter_map(|target| target.owner));
+    let usernames: HashMap<i32, String> = User::find()
+        .filter(entity::user::Column::Id.is_in(user_ids))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user.username))
+        .collect();
+
+    let items = reports
+        .into_iter()
+        .zip(targets)
+        .map(|(report, mut target)| {
+            if let Some(target) = &mut target {
+                target.owner_username = target.owner.and_then(|id| usernames.get(&id).cloned());
+            }
+
+            ReportResponse {
+                reporter_username: report.reporter.and_then(|id| usernames.get(&id).cloned()),
+                report,
+                target,
+            }
+        })
+        .collect();
+
+    Ok(Json(page.with_items(items)))
+}
+
+#[derive(Debug, Deserialize)]
+struct ActionParams {
+    action: ModerationActionKind,
+    note: Option<String>,
+}
+
+/// Deal with a report, which also closes every other open report about the same content
+async fn report_action(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(storage): Extension<SharedStorage>,
+    Path(report_id): Path<i32>,
+    Json(params): Json<ActionParams>,
+) -> Result<impl IntoResponse> {
+    use entity::report::Column;
+
+    let moderator = find_moderator(conn, &auth).await?;
+
+    let report = Report::find_by_id(report_id)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+    if report.status != ReportStatus::Open {
+        return Err(Error::BadRequest(
+            "This report has already been dealt with".to_string(),
+        ));
+    }
+
+    let txn = conn.begin().await?;
+
+    let target = find_target(&txn, report.target_kind, report.target_id).await?;
+    if target.is_none() && params.action != ModerationActionKind::Dismiss {
+        return Err(Error::BadRequest(
+            "The reported content no longer exists".to_string(),
+        ));
+    }
+    let target_user = target.and_then(|target| target.owner);
+
+    let mut image_keys = Vec::new();
+    match params.action {
+        ModerationActionKind::Hide => {
+            set_hidden(&txn, report.target_kind, report.target_id, true).await?;
+        }
+        ModerationActionKind::Approve => {
+            set_hidden(&txn, report.target_kind, report.target_id, false).await?;
+        }
+        ModerationActionKind::Delete => {
+            image_keys = stored_files(&txn, report.target_kind, report.target_id).await?;
+            delete_target(&txn, report.target_kind, report.target_id).await?;
+        }
+        ModerationActionKind::Warn if target_user.is_none() => {
+            return Err(Error::BadRequest(
+                "There's nobody responsible for this content to warn".to_string(),
+            ));
+        }
+        ModerationActionKind::Warn | ModerationActionKind::Dismiss => {}
+        ModerationActionKind::SetCover => {
+            return Err(Error::BadRequest(
+                "Covers are chosen from the image, not from a report".to_string(),
+            ));
+        }
+    }
+
+    let action = entity::moderation_action::ActiveModel {
+        moderator: Set(Some(moderator.id)),
+        kind: Set(params.action),
+        target_kind: Set(report.target_kind),
+        target_id: Set(report.target_id),
+        target_user: Set(target_user),
+        note: Set(params.note),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+
+    let status = match params.action {
+        ModerationActionKind::Dismiss => ReportStatus::Dismissed,
+        _ => ReportStatus::Resolved,
+    };
+    Report::update_many()
+        .col_expr(Column::Status, Expr::value(status))
+        .col_expr(Column::ResolvedAt, Expr::value(action.created_at))
+        .col_expr(Column::ResolvedBy, Expr::value(action.id))
+        .filter(Column::TargetKind.eq(report.target_kind))
+        .filter(Column::TargetId.eq(report.target_id))
+        .filter(Column::Status.eq(ReportStatus::Open))
+        .exec(&txn)
+        .await?;
+
+    txn.commit().await?;
+
+    delete_stored_files(&storage, &image_keys).await;
+
+    Ok(Json(action))
+}
+
+/// The audit trail of every moderation action, newest first
+async fn action_log(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Query(page_params): Query<PageParams>,
+) -> Result<impl IntoResponse> {
+    use entity::moderation_action::Column;
+
+    find_moderator(conn, &auth).await?;
+
+    let select = ModerationAction::find()
+        .order_by_desc(Column::CreatedAt)
+        .order_by_desc(Column::Id);
+
+    Ok(Json(fetch_page(conn, select, &page_params).await?))
+}
+
+#[derive(Debug, Serialize)]
+struct Warning {
+    target_kind: ModerationTarget,
+    target_id: i32,
+    note: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+/// Warnings moderators have given the caller. Who gave them is left out.
+async fn own_warnings(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+) -> Result<impl IntoResponse> {
+    use entity::moderation_action::Column;
+
+    let warnings: Vec<Warning> = ModerationAction::find()
+        .filter(Column::TargetUser.eq(auth.user_id))
+        .filter(Column::Kind.eq(ModerationActionKind::Warn))
+        .order_by_desc(Column::CreatedAt)
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|action| Warning {
+            target_kind: action.target_kind,
+            target_id: action.target_id,
+            note: action.note,
+            created_at: action.created_at,
+        })
+        .collect();
+
+    Ok(Json(warnings))
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/report", post(report_insert))
+        .route("/moderation/reports", get(report_queue))
+        .route("/moderation/reports/:report_id/action", post(report_action))
+        .route("/moderation/actions", get(action_log))
+        .route("/account/warnings", get(own_warnings))
+}
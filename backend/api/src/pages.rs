@@ -0,0 +1,387 @@
+use std::path::{Path as FsPath, PathBuf};
+
+use axum::{
+    extract::Path,
+    http::{header, StatusCode},
+    response::{Html, IntoResponse, Response},
+    routing::get,
+    Extension, Router,
+};
+use entity::prelude::*;
+use sea_orm::prelude::*;
+
+use crate::{
+    badge::score_summary,
+    catalog_image::sauce_covers,
+    error::{Error, Result},
+    markup::{escape, truncate},
+    ratings::{find_axis, OVERALL_AXIS_NAME},
+    review::find_visible_review,
+    review_image::review_images,
+    sauce::find_visible_sauce,
+    SiteSettings,
+};
+
+const SITE_NAME: &str = "Hot sauce ratings";
+
+/// Review text is cut down to this for link previews
+const MAX_DESCRIPTION_CHARS: usize = 200;
+
+/// The built frontend's `index.html`, which every page is served from so the Vue app can take
+/// over once it loads
+#[derive(Debug, Clone)]
+pub struct AppShell {
+    index_path: PathBuf,
+}
+
+impl AppShell {
+    pub fn new(frontend_dir: &FsPath) -> Self {
+        let index_path = frontend_dir.join("index.html");
+        if !index_path.is_file() {
+            log::warn!(
+                "{} doesn't exist yet, so pages will be served without the app until it's built",
+                index_path.display()
+            );
+        }
+
+        AppShell { index_path }
+    }
+
+    /// Read for every page, like the rest of the frontend, so a rebuild is picked up without a
+    /// restart. The old `index.html` would point at hashed assets that no longer exist.
+    async fn index_html(&self) -> Option<String> {
+        match tokio::fs::read_to_string(&self.index_path).await {
+            Ok(html) => Some(html),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                log::error!("Couldn't read {}: {e}", self.index_path.display());
+                None
+            }
+        }
+    }
+
+    /// The app's page with the given metadata filled in, or as it was built without any
+    async fn render(&self, meta: Option<&PageMeta>) -> String {
+        let title = meta.map_or(SITE_NAME.to_string(), |meta| {
+            format!("{} - {SITE_NAME}", meta.title)
+        });
+        let tags = meta.map(PageMeta::tags).unwrap_or_default();
+
+        let html = match self.index_html().await {
+            Some(html) => html,
+            None => {
+                return format!(
+                    "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"UTF-8\" />\n\
+                     <title>{}</title>\n{tags}</head>\n<body></body>\n</html>\n",
+                    escape(&title)
+                )
+            }
+        };
+
+        if meta.is_none() {
+            return html;
+        }
+
+        let mut html = match (html.find("<title>"), html.find("</title>")) {
+            (Some(start), Some(end)) if start < end => format!(
+                "{}<title>{}{}",
+                &html[..start],
+                escape(&title),
+                &html[end..]
+            ),
+            _ => html,
+        };
+        if let Some(head_end) = html.find("</head>") {
+            html.insert_str(head_end, &tags);
+        }
+
+        html
+    }
+
+    async fn page(&self, meta: Option<&PageMeta>) -> Response {
+        let status = match meta {
+            Some(_) => StatusCode::OK,
+            None => StatusCode::NOT_FOUND,
+        };
+
+        self.respond(status, meta).await
+    }
+
+    /// The app's page as it was built, for app routes that share a prefix with a page but aren't
+    /// one, like `/review/submit`
+    async fn app(&self) -> Response {
+        self.respond(StatusCode::OK, None).await
+    }
+
+    async fn respond(&self, status: StatusCode, meta: Option<&PageMeta>) -> Response {
+        (
+            status,
+            // The page points at whichever build of the app is current, so it shouldn't be kept
+            [(header::CACHE_CONTROL, "no-cache")],
+            Html(self.render(meta).await),
+        )
+            .into_response()
+    }
+}
+
+/// What a link to a page shows when it's shared
+#[derive(Debug)]
+struct PageMeta {
+    title: String,
+    description: String,
+    url: String,
+    image: Option<String>,
+    kind: &'static str,
+}
+
+impl PageMeta {
+    /// OpenGraph tags, for most chat apps, and their Twitter card equivalents
+    fn tags(&self) -> String {
+        let mut tags = vec![
+            ("name", "description", &self.description),
+            ("property", "og:title", &self.title),
+            ("property", "og:description", &self.description),
+            ("property", "og:url", &self.url),
+            ("name", "twitter:title", &self.title),
+            ("name", "twitter:description", &self.description),
+        ];
+        if let Some(image) = &self.image {
+            tags.push(("property", "og:image", image));
+            tags.push(("name", "twitter:image", image));
+        }
+
+        let card = match self.image {
+            Some(_) => "summary_large_image",
+            None => "summary",
+        };
+        let mut html = format!(
+            "<meta property=\"og:site_name\" content=\"{}\" />\n\
+             <meta property=\"og:type\" content=\"{}\" />\n\
+             <meta name=\"twitter:card\" content=\"{card}\" />\n\
+             <link rel=\"canonical\" href=\"{}\" />\n",
+            escape(SITE_NAME),
+            self.kind,
+            escape(&self.url)
+        );
+        for (attribute, name, content) in tags {
+            html.push_str(&format!(
+                "<meta {attribute}=\"{name}\" content=\"{}\" />\n",
+                escape(content)
+            ));
+        }
+
+        html
+    }
+}
+
+async fn find_sauce_and_manufacturer(
+    conn: &DatabaseConnection,
+    sauce_id: i32,
+) -> Result<(entity::sauce::Model, entity::manufacturer::Model)> {
+    let sauce = find_visible_sauce(conn, sauce_id).await?;
+    let manufacturer = sauce
+        .find_related(Manufacturer)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    Ok((sauce, manufacturer))
+}
+
+/// Pages for things that don't exist, or that a crawler isn't allowed to see, are still served so
+/// the app can explain what happened
+fn meta_or_missing(meta: Result<PageMeta>) -> Result<Option<PageMeta>> {
+    match meta {
+        Ok(meta) => Ok(Some(meta)),
+        Err(Error::NotFound | Error::Unauthorized | Error::Forbidden) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+async fn sauce_meta(
+    conn: &DatabaseConnection,
+    site: &SiteSettings,
+    sauce_id: i32,
+) -> Result<PageMeta> {
+    let (sauce, manufacturer) = find_sauce_and_manufacturer(conn, sauce_id).await?;
+    let (summary, _) = score_summary(conn, sauce.id).await?;
+    let image = sauce_covers(conn, vec![sauce.id])
+        .await?
+        .remove(&sauce.id)
+        .map(|cover| format!("{}{}", site.public_url, cover.medium_url));
+
+    Ok(PageMeta {
+        title: format!("{} by {}", sauce.name, manufacturer.name),
+        description: format!("Community rating: {summary}"),
+        url: format!("{}/sauce/{}", site.public_url, sauce.id),
+        image,
+        kind: "website",
+    })
+}
+
+async fn sauce_page(
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(site): Extension<SiteSettings>,
+    Extension(shell): Extension<AppShell>,
+    Path(sauce_id): Path<String>,
+) -> Result<Response> {
+    let sauce_id = match sauce_id.parse() {
+        Ok(sauce_id) => sauce_id,
+        Err(_) => return Ok(shell.app().await),
+    };
+    let meta = meta_or_missing(sauce_meta(conn, &site, sauce_id).await)?;
+    Ok(shell.page(meta.as_ref()).await)
+}
+
+/// Link previews are fetched without being logged in, so only reviews anyone can see get one
+async fn review_meta(
+    conn: &DatabaseConnection,
+    site: &SiteSettings,
+    review_id: i32,
+) -> Result<PageMeta> {
+    let review = find_visible_review(conn, None, review_id).await?;
+    let (sauce, manufacturer) = find_sauce_and_manufacturer(conn, review.sauce).await?;
+    let reviewer = User::find_by_id(review.user)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    let mut description = Vec::new();
+    if let Some(axis) = find_axis(conn, OVERALL_AXIS_NAME).await? {
+        if let Some(rating) = ReviewRating::find()
+            .filter(entity::review_rating::Column::Review.eq(review.id))
+            .filter(entity::review_rating::Column::RatingAxis.eq(axis.id))
+            .one(conn)
+            .await?
+        {
+            description.push(format!("{}/{}", rating.rating, axis.max_value));
+        }
+    }
+    if let Some(text) = review.text.as_deref().map(str::trim) {
+        if !text.is_empty() {
+            description.push(truncate(text, MAX_DESCRIPTION_CHARS));
+        }
+    }
+    if description.is_empty() {
+        description.push(format!("A review of {}", sauce.name));
+    }
+
+    // The review's own photo if it has one, otherwise the sauce's
+    let image = match review_images(conn, vec![review.id])
+        .await?
+        .remove(&review.id)
+        .and_then(|images| images.into_iter().next())
+    {
+        Some(image) => Some(image.url),
+        None => sauce_covers(conn, vec![sauce.id])
+            .await?
+            .remove(&sauce.id)
+            .map(|cover| cover.medium_url),
+    };
+
+    Ok(PageMeta {
+        title: format!(
+            "{}'s review of {} by {}",
+            reviewer.username, sauce.name, manufacturer.name
+        ),
+        description: description.join(" - "),
+        url: format!("{}/review/{}", site.public_url, review.id),
+        image: image.map(|url| format!("{}{url}", site.public_url)),
+        kind: "article",
+    })
+}
+
+async fn review_page(
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(site): Extension<SiteSettings>,
+    Extension(shell): Extension<AppShell>,
+    Path(review_id): Path<String>,
+) -> Result<Response> {
+    let review_id = match review_id.parse() {
+        Ok(review_id) => review_id,
+        Err(_) => return Ok(shell.app().await),
+    };
+    let meta = meta_or_missing(review_meta(conn, &site, review_id).await)?;
+    Ok(shell.page(meta.as_ref()).await)
+}
+
+/// Pages served outside of the API, at the same paths as the app uses for them
+pub fn router() -> Router {
+    Router::new()
+        .route("/sauce/:sauce_id", get(sauce_page))
+        .route("/review/:review_id", get(review_page))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{Body, HttpBody},
+        http::Request,
+    };
+    use tower::ServiceExt;
+
+    use super::*;
+
+    const INDEX_HTML: &str = "<!DOCTYPE html><html><head><title>App</title></head>\
+                              <body><div id=\"app\"></div></body></html>";
+
+    /// A frontend directory of its own for each test, holding just `index.html`
+    fn frontend_dir(test: &str, index_html: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pages-{test}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("index.html"), index_html).unwrap();
+        dir
+    }
+
+    async fn get_page(shell: &AppShell, path: &str) -> (StatusCode, String) {
+        let app = router()
+            .layer(Extension(DatabaseConnection::Disconnected))
+            .layer(Extension(SiteSettings {
+                public_url: "http://localhost:3030".to_string(),
+            }))
+            .layer(Extension(shell.clone()));
+
+        let response = app
+            .oneshot(Request::get(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+
+        let mut body = response.into_body();
+        let mut bytes = Vec::new();
+        while let Some(chunk) = body.data().await {
+            bytes.extend_from_slice(&chunk.unwrap());
+        }
+
+        (status, String::from_utf8(bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn app_routes_under_page_paths_get_the_app() {
+        let dir = frontend_dir("app_routes", INDEX_HTML);
+        let shell = AppShell::new(&dir);
+        for path in ["/review/submit", "/sauce/abc"] {
+            assert_eq!(
+                get_page(&shell, path).await,
+                (StatusCode::OK, INDEX_HTML.to_string()),
+                "{path}"
+            );
+        }
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn rebuilt_app_is_served_without_a_restart() {
+        let dir = frontend_dir("rebuilt", INDEX_HTML);
+        let shell = AppShell::new(&dir);
+        let (_, before) = get_page(&shell, "/review/submit").await;
+
+        let rebuilt = INDEX_HTML.replace("<title>App</title>", "<title>Rebuilt</title>");
+        std::fs::write(dir.join("index.html"), &rebuilt).unwrap();
+        let (_, after) = get_page(&shell, "/review/submit").await;
+
+        assert_eq!(before, INDEX_HTML);
+        assert_eq!(after, rebuilt);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,178 @@
+use sea_orm::{prelude::*, PaginatorTrait, Select};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+const DEFAULT_PER_PAGE: usize = 20;
+const MAX_PER_PAGE: usize = 100;
+
+/// Query parameters for endpoints returning a [`Page`]. Pages are numbered from zero.
+#[derive(Debug, Deserialize)]
+pub struct PageParams {
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+}
+
+impl PageParams {
+    pub fn page(&self) -> usize {
+        self.page.unwrap_or(0)
+    }
+
+    pub fn per_page(&self) -> usize {
+        self.per_page
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .clamp(1, MAX_PER_PAGE)
+    }
+
+    /// How many items come before the requested page. Pages too far in to be queried are rejected,
+    /// rather than overflowing.
+    pub fn offset(&self) -> Result<usize> {
+        self.page()
+            .checked_mul(self.per_page())
+            .filter(|&offset| i64::try_from(offset).is_ok())
+            .ok_or_else(|| Error::BadRequest(format!("Page {} is out of range", self.page())))
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub page: usize,
+    pub per_page: usize,
+    pub total_items: usize,
+    pub total_pages: usize,
+}
+
+impl<T> Page<T> {
+    pub fn with_items<U>(self, items: Vec<U>) -> Page<U> {
+        Page {
+            items,
+            page: self.page,
+            per_page: self.per_page,
+            total_items: self.total_items,
+            total_pages: self.total_pages,
+        }
+    }
+}
+
+pub async fn fetch_page<E>(
+    conn: &DatabaseConnection,
+    select: Select<E>,
+    params: &PageParams,
+) -> Result<Page<E::Model>>
+where
+    E: EntityTrait,
+    E::Model: Sync,
+{
+    let per_page = params.per_page();
+    params.offset()?;
+    let paginator = select.paginate(conn, per_page);
+
+    let total_items = paginator.num_items().await?;
+    let items = paginator.fetch_page(params.page()).await?;
+
+    Ok(Page {
+        items,
+        page: params.page(),
+        per_page,
+        total_items,
+        total_pages: total_items.div_ceil(per_page),
+    })
+}
+
+/// Page through items that had to be loaded and ordered in memory
+pub fn page_from_vec<T>(mut items: Vec<T>, params: &PageParams) -> Result<Page<T>> {
+    let per_page = params.per_page();
+    let total_items = items.len();
+
+    let start = params.offset()?.min(total_items);
+    let end = (start + per_page).min(total_items);
+    let items = items.drain(start..end).collect();
+
+    Ok(Page {
+        items,
+        page: params.page(),
+        per_page,
+        total_items,
+        total_pages: total_items.div_ceil(per_page),
+    })
+}
+
+/// Query parameters for endpoints returning a [`CursorPage`]
+#[derive(Debug, Deserialize)]
+pub struct CursorParams {
+    /// Only return items older than this cursor
+    pub before: Option<i32>,
+    pub limit: Option<usize>,
+}
+
+impl CursorParams {
+    pub fn limit(&self) -> usize {
+        self.limit
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .clamp(1, MAX_PER_PAGE)
+    }
+}
+
+/// A page of items from a list that's being added to while it's read, so page numbers would drift
+#[derive(Debug, Serialize)]
+pub struct CursorPage<T> {
+    pub items: Vec<T>,
+
+    /// Pass as `before` to fetch the next page. Missing once there's nothing further to fetch.
+    pub next_cursor: Option<i32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(page: usize, per_page: usize) -> PageParams {
+        PageParams {
+            page: Some(page),
+            per_page: Some(per_page),
+        }
+    }
+
+    #[test]
+    fn offset_is_page_times_per_page() {
+        assert_eq!(params(0, 20).offset().unwrap(), 0);
+        assert_eq!(params(3, 20).offset().unwrap(), 60);
+        // per_page is clamped before the offset is worked out
+        assert_eq!(params(2, 1000).offset().unwrap(), 200);
+    }
+
+    #[test]
+    fn offsets_that_overflow_are_rejected() {
+        assert!(matches!(
+            params(usize::MAX, 20).offset(),
+            Err(Error::BadRequest(_))
+        ));
+        assert!(matches!(
+            params(usize::MAX / 100, 100).offset(),
+            Err(Error::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn pages_from_vec() {
+        let items: Vec<i32> = (0..5).collect();
+
+        assert_eq!(
+            page_from_vec(items.clone(), &params(1, 2)).unwrap().items,
+            [2, 3]
+        );
+        assert_eq!(
+            page_from_vec(items.clone(), &params(2, 2)).unwrap().items,
+            [4]
+        );
+        assert!(page_from_vec(items.clone(), &params(3, 2))
+            .unwrap()
+            .items
+            .is_empty());
+        assert!(matches!(
+            page_from_vec(items, &params(usize::MAX, 2)),
+            Err(Error::BadRequest(_))
+        ));
+    }
+}
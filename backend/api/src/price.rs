@@ -0,0 +1,352 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap, HashSet},
+};
+
+use axum::{
+    extract::{Path, Query},
+    response::IntoResponse,
+    routing::{delete, get},
+    Extension, Json, Router,
+};
+use chrono::{Duration, NaiveDate, Utc};
+use entity::prelude::*;
+use sea_orm::{prelude::*, QueryOrder, Set};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::AuthenticatedUser,
+    error::{Error, Result},
+    moderation::find_moderator,
+    pagination::{fetch_page, PageParams},
+    sauce::find_visible_sauce,
+};
+
+/// Observations older than this aren't counted as a current price
+const CURRENT_PRICE_MAX_AGE_DAYS: i64 = 90;
+const MAX_BOTTLE_SIZE_ML: i32 = 5000;
+/// A million in major units, well beyond any real bottle of sauce
+const MAX_PRICE_MINOR: i64 = 100_000_000;
+
+async fn retailer_list(
+    Extension(ref conn): Extension<DatabaseConnection>,
+) -> Result<impl IntoResponse> {
+    let retailers = Retailer::find()
+        .order_by_asc(entity::retailer::Column::Name)
+        .all(conn)
+        .await?;
+
+    Ok(Json(retailers))
+}
+
+#[derive(Debug, Deserialize)]
+struct NewRetailer {
+    name: String,
+    website: Option<String>,
+}
+
+/// Only moderators can add retailers, since their websites are shown as links next to prices
+async fn retailer_insert(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Json(new): Json<NewRetailer>,
+) -> Result<impl IntoResponse> {
+    find_moderator(conn, &auth).await?;
+
+    let name = new.name.trim().to_string();
+    if name.is_empty() {
+        return Err(Error::BadRequest("Retailers need a name".to_string()));
+    }
+
+    if let Some(existing) = Retailer::find()
+        .filter(entity::retailer::Column::Name.eq(name.clone()))
+        .one(conn)
+        .await?
+    {
+        return Ok(Json(existing));
+    }
+
+    let website = new
+        .website
+        .map(|website| website.trim().to_string())
+        .filter(|website| !website.is_empty());
+    if website
+        .as_ref()
+        .is_some_and(|website| !(website.starts_with("https://") || website.starts_with("http://")))
+    {
+        return Err(Error::BadRequest(
+            "Retailer websites need to be http or https links".to_string(),
+        ));
+    }
+
+    let retailer = entity::retailer::ActiveModel {
+        name: Set(name),
+        website: Set(website),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await?;
+
+    Ok(Json(retailer))
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct PriceResponse {
+    #[serde(flatten)]
+    observation: entity::price_observation::Model,
+    retailer_name: String,
+    /// The price scaled to a 100ml bottle, in minor units rounded to the nearest whole unit, so
+    /// different sizes can be compared
+    price_per_100ml_minor: i64,
+}
+
+fn price_per_100ml_minor(observation: &entity::price_observation::Model) -> i64 {
+    let size_ml = i64::from(observation.size_ml);
+    (observation.price_minor * 100 + size_ml / 2) / size_ml
+}
+
+/// Compare the value of two observations by price per ml, without the rounding of
+/// `price_per_100ml_minor`
+fn cmp_value(
+    a: &entity::price_observation::Model,
+    b: &entity::price_observation::Model,
+) -> Ordering {
+    (i128::from(a.price_minor) * i128::from(b.size_ml))
+        .cmp(&(i128::from(b.price_minor) * i128::from(a.size_ml)))
+}
+
+async fn price_responses(
+    conn: &DatabaseConnection,
+    observations: Vec<entity::price_observation::Model>,
+) -> Result<Vec<PriceResponse>> {
+    let retailer_ids: HashSet<i32> = observations
+        .iter()
+        .map(|observation| observation.retailer)
+        .collect();
+    let retailers: HashMap<i32, String> = Retailer::find()
+        .filter(entity::retailer::Column::Id.is_in(retailer_ids))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|retailer| (retailer.id, retailer.name))
+        .collect();
+
+    Ok(observations
+        .into_iter()
+        .map(|observation| PriceResponse {
+            retailer_name: retailers
+                .get(&observation.retailer)
+                .cloned()
+                .unwrap_or_default(),
+            price_per_100ml_minor: price_per_100ml_minor(&observation),
+            observation,
+        })
+        .collect())
+}
+
+#[derive(Debug, Deserialize)]
+struct PriceHistoryQuery {
+    retailer: Option<i32>,
+    currency: Option<String>,
+}
+
+/// Every price seen for a sauce, most recently observed first
+async fn price_history(
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(sauce_id): Path<i32>,
+    Query(query): Query<PriceHistoryQuery>,
+    Query(page_params): Query<PageParams>,
+) -> Result<impl IntoResponse> {
+    use entity::price_observation::Column;
+
+    let sauce = find_visible_sauce(conn, sauce_id).await?;
+
+    let mut select = PriceObservation::find()
+        .filter(Column::Sauce.eq(sauce.id))
+        .order_by_desc(Column::ObservedOn)
+        .order_by_desc(Column::Id);
+    if let Some(retailer) = query.retailer {
+        select = select.filter(Column::Retailer.eq(retailer));
+    }
+    if let Some(currency) = query.currency {
+        select = select.filter(Column::Currency.eq(currency.to_ascii_uppercase()));
+    }
+
+    let mut page = fetch_page(conn, select, &page_params).await?;
+    let observations = std::mem::take(&mut page.items);
+
+    Ok(Json(
+        page.with_items(price_responses(conn, observations).await?),
+    ))
+}
+
+#[derive(Debug, Serialize)]
+struct CurrentPrices {
+    currency: String,
+    /// The lowest price on the shelf, whatever the bottle size
+    cheapest: PriceResponse,
+    /// The lowest price once bottle sizes are taken into account
+    best_value: PriceResponse,
+}
+
+/// The cheapest places to buy a sauce right now, per currency. Only the latest observation of each
+/// bottle size at each retailer counts, and only if it's recent.
+async fn current_prices(
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(sauce_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    use entity::price_observation::Column;
+
+    let sauce = find_visible_sauce(conn, sauce_id).await?;
+
+    let cutoff = Utc::now().date_naive() - Duration::days(CURRENT_PRICE_MAX_AGE_DAYS);
+    let observations = PriceObservation::find()
+        .filter(Column::Sauce.eq(sauce.id))
+        .filter(Column::ObservedOn.gte(cutoff))
+        .order_by_desc(Column::ObservedOn)
+        .order_by_desc(Column::Id)
+        .all(conn)
+        .await?;
+
+    let mut seen = HashSet::new();
+    let latest: Vec<_> = observations
+        .into_iter()
+        .filter(|observation| {
+            seen.insert((
+                observation.retailer,
+                observation.currency.clone(),
+                observation.size_ml,
+            ))
+        })
+        .collect();
+
+    let mut by_currency: BTreeMap<String, Vec<PriceResponse>> = BTreeMap::new();
+    for response in price_responses(conn, latest).await? {
+        by_currency
+            .entry(response.observation.currency.clone())
+            .or_default()
+            .push(response);
+    }
+
+    let current: Vec<CurrentPrices> = by_currency
+        .into_iter()
+        .filter_map(|(currency, prices)| {
+            let cheapest = prices
+                .iter()
+                .min_by_key(|price| price.observation.price_minor)?
+                .clone();
+            let best_value = prices
+                .iter()
+                .min_by(|a, b| cmp_value(&a.observation, &b.observation))?
+                .clone();
+
+            Some(CurrentPrices {
+                currency,
+                cheapest,
+                best_value,
+            })
+        })
+        .collect();
+
+    Ok(Json(current))
+}
+
+#[derive(Debug, Deserialize)]
+struct NewPrice {
+    retailer: i32,
+    /// In the currency's minor unit, e.g. 499 for £4.99
+    price_minor: i64,
+    currency: String,
+    size_ml: i32,
+    /// Defaults to today
+    observed_on: Option<NaiveDate>,
+}
+
+async fn price_insert(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(sauce_id): Path<i32>,
+    Json(new): Json<NewPrice>,
+) -> Result<impl IntoResponse> {
+    let sauce = find_visible_sauce(conn, sauce_id).await?;
+    let retailer = Retailer::find_by_id(new.retailer)
+        .one(conn)
+        .await?
+        .ok_or_else(|| Error::BadRequest(format!("Unknown retailer {}", new.retailer)))?;
+
+    if new.price_minor <= 0 {
+        return Err(Error::BadRequest("Prices must be above zero".to_string()));
+    }
+    if new.price_minor > MAX_PRICE_MINOR {
+        return Err(Error::BadRequest("That price is too high".to_string()));
+    }
+    // ISO 4217 codes, e.g. GBP or USD
+    let currency = new.currency.trim().to_ascii_uppercase();
+    if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(Error::BadRequest(format!(
+            "\"{}\" isn't a currency code",
+            new.currency
+        )));
+    }
+    if !(1..=MAX_BOTTLE_SIZE_ML).contains(&new.size_ml) {
+        return Err(Error::BadRequest(format!(
+            "Bottle sizes must be between 1 and {MAX_BOTTLE_SIZE_ML}ml"
+        )));
+    }
+    let today = Utc::now().date_naive();
+    let observed_on = new.observed_on.unwrap_or(today);
+    if observed_on > today {
+        return Err(Error::BadRequest(
+            "Prices can't be observed in the future".to_string(),
+        ));
+    }
+
+    let observation = entity::price_observation::ActiveModel {
+        sauce: Set(sauce.id),
+        retailer: Set(retailer.id),
+        price_minor: Set(new.price_minor),
+        currency: Set(currency),
+        size_ml: Set(new.size_ml),
+        observed_on: Set(observed_on),
+        submitted_by: Set(Some(auth.user_id)),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await?;
+
+    let mut responses = price_responses(conn, vec![observation]).await?;
+    Ok(Json(responses.pop()))
+}
+
+/// Prices can be removed by whoever submitted them, or by a moderator
+async fn price_delete(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(price_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let observation = PriceObservation::find_by_id(price_id)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+    if observation.submitted_by != Some(auth.user_id) {
+        find_moderator(conn, &auth).await?;
+    }
+
+    PriceObservation::delete_by_id(observation.id)
+        .exec(conn)
+        .await?;
+
+    Ok(Json(()))
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/retailer", get(retailer_list).put(retailer_insert))
+        .route(
+            "/sauce/:sauce_id/prices",
+            get(price_history).post(price_insert),
+        )
+        .route("/sauce/:sauce_id/prices/current", get(current_prices))
+        .route("/price/:price_id", delete(price_delete))
+}
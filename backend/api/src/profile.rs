@@ -1,10 +1,20 @@
-use axum::{response::IntoResponse, routing::get, Extension, Json, Router};
-use entity::prelude::*;
-use sea_orm::{DatabaseConnection, EntityTrait};
+use axum::{
+    extract::{Path, Query},
+    response::IntoResponse,
+    routing::{get, put},
+    Extension, Json, Router,
+};
+use chrono::{DateTime, Utc};
+use entity::{prelude::*, sea_orm_active_enums::ProfilePrivacy};
+use sea_orm::{prelude::*, PaginatorTrait, QueryOrder, Set};
+use serde::{Deserialize, Serialize};
 
 use crate::{
     auth::AuthenticatedUser,
+    block::ensure_not_ignored,
     error::{Error, Result},
+    pagination::{fetch_page, PageParams},
+    review::review_responses,
 };
 
 async fn basic_profile(
@@ -19,6 +29,146 @@ async fn basic_profile(
     Ok(Json(user))
 }
 
+pub async fn find_user_by_username(
+    conn: &DatabaseConnection,
+    username: &str,
+) -> Result<entity::user::Model> {
+    User::find()
+        .filter(entity::user::Column::Username.eq(username))
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)
+}
+
+/// Check that the given viewer is allowed to see the user's profile under their privacy setting
+pub fn ensure_profile_visible(
+    viewer: Option<&AuthenticatedUser>,
+    user: &entity::user::Model,
+) -> Result<()> {
+    let is_self = viewer.is_some_and(|viewer| viewer.user_id == user.id);
+
+    // Users hidden by a moderator disappear for everyone else
+    if user.hidden_at.is_some() && !is_self {
+        return Err(Error::NotFound);
+    }
+
+    match user.privacy {
+        ProfilePrivacy::Public => Ok(()),
+        ProfilePrivacy::LoggedIn if viewer.is_some() => Ok(()),
+        ProfilePrivacy::LoggedIn => Err(Error::Unauthorized),
+        ProfilePrivacy::Private if is_self => Ok(()),
+        ProfilePrivacy::Private => Err(Error::Forbidden),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct PublicProfile {
+    id: i32,
+    username: String,
+    review_count: usize,
+    sauces_tried: usize,
+    first_review_at: Option<DateTime<Utc>>,
+    latest_review_at: Option<DateTime<Utc>>,
+    taste_profile_public: bool,
+    follower_count: usize,
+    following_count: usize,
+}
+
+async fn public_profile(
+    auth: Option<AuthenticatedUser>,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(username): Path<String>,
+) -> Result<impl IntoResponse> {
+    let user = find_user_by_username(conn, &username).await?;
+    ensure_profile_visible(auth.as_ref(), &user)?;
+
+    // Counted the same way as the reviews listed by `user_reviews`
+    let reviews = Review::find()
+        .filter(entity::review::Column::User.eq(user.id))
+        .filter(entity::review::Column::HiddenAt.is_null())
+        .all(conn)
+        .await?;
+
+    let follower_count = Follow::find()
+        .filter(entity::follow::Column::Followee.eq(user.id))
+        .count(conn)
+        .await?;
+    let following_count = Follow::find()
+        .filter(entity::follow::Column::Follower.eq(user.id))
+        .count(conn)
+        .await?;
+
+    let mut sauces: Vec<i32> = reviews.iter().map(|review| review.sauce).collect();
+    sauces.sort_unstable();
+    sauces.dedup();
+
+    Ok(Json(PublicProfile {
+        id: user.id,
+        username: user.username,
+        review_count: reviews.len(),
+        sauces_tried: sauces.len(),
+        first_review_at: reviews.iter().map(|review| review.timestamp).min(),
+        latest_review_at: reviews.iter().map(|review| review.timestamp).max(),
+        taste_profile_public: user.taste_profile_public,
+        follower_count,
+        following_count,
+    }))
+}
+
+async fn user_reviews(
+    auth: Option<AuthenticatedUser>,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(username): Path<String>,
+    Query(page_params): Query<PageParams>,
+) -> Result<impl IntoResponse> {
+    use entity::review::Column;
+
+    let user = find_user_by_username(conn, &username).await?;
+    ensure_profile_visible(auth.as_ref(), &user)?;
+    ensure_not_ignored(conn, auth.as_ref(), user.id).await?;
+
+    let select = Review::find()
+        .filter(Column::User.eq(user.id))
+        .filter(Column::HiddenAt.is_null())
+        .order_by_desc(Column::Timestamp)
+        .order_by_desc(Column::Id);
+
+    let mut page = fetch_page(conn, select, &page_params).await?;
+    let reviews = std::mem::take(&mut page.items);
+
+    Ok(Json(
+        page.with_items(review_responses(conn, reviews).await?),
+    ))
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct PrivacySetting {
+    privacy: ProfilePrivacy,
+}
+
+async fn set_privacy(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Json(setting): Json<PrivacySetting>,
+) -> Result<impl IntoResponse> {
+    let user = User::find_by_id(auth.user_id)
+        .one(conn)
+        .await?
+        .ok_or(Error::Unauthorized)?;
+
+    let mut user: entity::user::ActiveModel = user.into();
+    user.privacy = Set(setting.privacy);
+    let user = user.update(conn).await?;
+
+    Ok(Json(PrivacySetting {
+        privacy: user.privacy,
+    }))
+}
+
 pub fn router() -> Router {
-    Router::new().route("/basic_profile", get(basic_profile))
+    Router::new()
+        .route("/basic_profile", get(basic_profile))
+        .route("/profile/privacy", put(set_privacy))
+        .route("/user/:username", get(public_profile))
+        .route("/user/:username/reviews", get(user_reviews))
 }
@@ -0,0 +1,304 @@
+use std::{
+    collections::HashMap,
+    f64::consts::{FRAC_PI_2, TAU},
+    fmt::{self, Write},
+};
+
+use axum::{
+    extract::{Path, Query},
+    http::header,
+    response::IntoResponse,
+    routing::get,
+    Extension, Router,
+};
+use entity::prelude::*;
+use sea_orm::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    auth::AuthenticatedUser,
+    block::ensure_not_ignored,
+    error::{Error, Result},
+    markup::{escape, truncate},
+    profile::{ensure_profile_visible, find_user_by_username},
+    ratings::sauce_axis_averages,
+    sauce::find_visible_sauce,
+};
+
+const DEFAULT_SIZE: u32 = 400;
+const MIN_SIZE: u32 = 120;
+const MAX_SIZE: u32 = 1200;
+
+/// Long sauce names are shortened so the legend stays inside the chart
+const MAX_LEGEND_LABEL_CHARS: usize = 40;
+
+/// Number of rings drawn between the centre and the edge of the chart
+const GRID_RINGS: usize = 4;
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Theme {
+    #[default]
+    Light,
+    Dark,
+}
+
+struct Palette {
+    background: &'static str,
+    grid: &'static str,
+    text: &'static str,
+    primary: &'static str,
+    overlay: &'static str,
+}
+
+impl Theme {
+    fn palette(self) -> Palette {
+        match self {
+            Theme::Light => Palette {
+                background: "#ffffff",
+                grid: "#d4d4d4",
+                text: "#333333",
+                primary: "#d62828",
+                overlay: "#1d7fbf",
+            },
+            Theme::Dark => Palette {
+                background: "#1e1e1e",
+                grid: "#4a4a4a",
+                text: "#e0e0e0",
+                primary: "#ff6b4a",
+                overlay: "#4fb3ff",
+            },
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RadarQuery {
+    size: Option<u32>,
+    #[serde(default)]
+    theme: Theme,
+    /// Another sauce to draw over the top
+    compare: Option<i32>,
+    /// A user whose own ratings of the sauce are drawn over the top
+    user: Option<String>,
+}
+
+/// One shape drawn on the chart
+struct Series {
+    label: String,
+    averages: HashMap<i32, f64>,
+}
+
+struct RadarChart<'a> {
+    size: f64,
+    palette: Palette,
+    axes: &'a [entity::rating_axis::Model],
+    series: Series,
+    overlay: Option<Series>,
+}
+
+impl RadarChart<'_> {
+    fn centre(&self) -> (f64, f64) {
+        // Leave room underneath for the legend
+        match self.overlay {
+            Some(_) => (self.size / 2.0, self.size * 0.42),
+            None => (self.size / 2.0, self.size / 2.0),
+        }
+    }
+
+    fn radius(&self) -> f64 {
+        self.size * 0.25
+    }
+
+    fn font_size(&self) -> f64 {
+        (self.size / 32.0).max(8.0)
+    }
+
+    /// Where a point a given fraction of the way out along an axis is. The first axis points
+    /// straight up and the rest go round clockwise.
+    fn point(&self, axis_index: usize, fraction: f64) -> (f64, f64) {
+        let (cx, cy) = self.centre();
+        let angle = TAU * axis_index as f64 / self.axes.len() as f64 - FRAC_PI_2;
+        let distance = self.radius() * fraction;
+        (cx + distance * angle.cos(), cy + distance * angle.sin())
+    }
+
+    fn polygon_points(&self, fractions: impl Iterator<Item = f64>) -> String {
+        fractions
+            .enumerate()
+            .map(|(i, fraction)| {
+                let (x, y) = self.point(i, fraction);
+                format!("{x:.1},{y:.1}")
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Axes nobody has rated are drawn at the centre
+    fn fractions<'s>(&'s self, series: &'s Series) -> impl Iterator<Item = f64> + 's {
+        self.axes.iter().map(|axis| {
+            let range = axis.max_value - axis.min_value;
+            series
+                .averages
+                .get(&axis.id)
+                .map(|average| ((average - axis.min_value) / range).clamp(0.0, 1.0))
+                .unwrap_or(0.0)
+        })
+    }
+
+    fn render(&self) -> std::result::Result<String, fmt::Error> {
+        let size = self.size;
+        let palette = &self.palette;
+        let font_size = self.font_size();
+        let mut svg = String::new();
+
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {size} {size}" font-family="sans-serif" font-size="{font_size:.1}">"#
+        )?;
+        write!(svg, "<title>{}", escape(&self.series.label))?;
+        if let Some(overlay) = &self.overlay {
+            write!(svg, " vs {}", escape(&overlay.label))?;
+        }
+        writeln!(svg, "</title>")?;
+        writeln!(
+            svg,
+            r#"<rect width="100%" height="100%" fill="{}"/>"#,
+            palette.background
+        )?;
+
+        for ring in 1..=GRID_RINGS {
+            let fraction = ring as f64 / GRID_RINGS as f64;
+            writeln!(
+                svg,
+                r#"<polygon points="{}" fill="none" stroke="{}" stroke-width="1"/>"#,
+                self.polygon_points(self.axes.iter().map(|_| fraction)),
+                palette.grid
+            )?;
+        }
+
+        let (cx, cy) = self.centre();
+        for (i, axis) in self.axes.iter().enumerate() {
+            let (x, y) = self.point(i, 1.0);
+            writeln!(
+                svg,
+                r#"<line x1="{cx:.1}" y1="{cy:.1}" x2="{x:.1}" y2="{y:.1}" stroke="{}" stroke-width="1"/>"#,
+                palette.grid
+            )?;
+
+            let (x, y) = self.point(i, 1.12);
+            let anchor = match x - cx {
+                dx if dx.abs() < 1.0 => "middle",
+                dx if dx > 0.0 => "start",
+                _ => "end",
+            };
+            writeln!(
+                svg,
+                r#"<text x="{x:.1}" y="{y:.1}" text-anchor="{anchor}" dominant-baseline="middle" fill="{}">{}</text>"#,
+                palette.text,
+                escape(&axis.name)
+            )?;
+        }
+
+        let mut shapes = vec![(&self.series, palette.primary)];
+        if let Some(overlay) = &self.overlay {
+            shapes.push((overlay, palette.overlay));
+        }
+        for (series, colour) in &shapes {
+            writeln!(
+                svg,
+                r#"<polygon points="{}" fill="{colour}" fill-opacity="0.3" stroke="{colour}" stroke-width="2" stroke-linejoin="round"/>"#,
+                self.polygon_points(self.fractions(series))
+            )?;
+        }
+
+        if self.overlay.is_some() {
+            let x = size * 0.06;
+            for (i, (series, colour)) in shapes.iter().enumerate() {
+                let y = size - font_size * 1.5 * (shapes.len() - i) as f64;
+                writeln!(
+                    svg,
+                    r#"<rect x="{x:.1}" y="{:.1}" width="{font_size:.1}" height="{font_size:.1}" fill="{colour}"/>"#,
+                    y - font_size / 2.0
+                )?;
+                writeln!(
+                    svg,
+                    r#"<text x="{:.1}" y="{y:.1}" dominant-baseline="middle" fill="{}">{}</text>"#,
+                    x + font_size * 1.4,
+                    palette.text,
+                    escape(&truncate(&series.label, MAX_LEGEND_LABEL_CHARS))
+                )?;
+            }
+        }
+
+        writeln!(svg, "</svg>")?;
+        Ok(svg)
+    }
+}
+
+/// A radar chart of a sauce's average scores on each rating axis, as a standalone SVG that can be
+/// embedded anywhere an image can
+async fn sauce_radar(
+    auth: Option<AuthenticatedUser>,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(sauce_id): Path<i32>,
+    Query(query): Query<RadarQuery>,
+) -> Result<impl IntoResponse> {
+    let sauce = find_visible_sauce(conn, sauce_id).await?;
+
+    let overlay = match (query.compare, &query.user) {
+        (Some(_), Some(_)) => {
+            return Err(Error::BadRequest(
+                "Either another sauce or a user can be drawn over the chart, not both".to_string(),
+            ))
+        }
+        (Some(other_id), None) => {
+            let other = find_visible_sauce(conn, other_id).await?;
+            Some(Series {
+                label: other.name,
+                averages: sauce_axis_averages(conn, other.id, None).await?,
+            })
+        }
+        (None, Some(username)) => {
+            let user = find_user_by_username(conn, username).await?;
+            ensure_profile_visible(auth.as_ref(), &user)?;
+            ensure_not_ignored(conn, auth.as_ref(), user.id).await?;
+            Some(Series {
+                averages: sauce_axis_averages(conn, sauce.id, Some(user.id)).await?,
+                label: user.username,
+            })
+        }
+        (None, None) => None,
+    };
+
+    // A user's ratings might not be public, so only let shared caches keep the plain chart
+    let cache_control = match query.user {
+        Some(_) => "private, max-age=300",
+        None => "public, max-age=300",
+    };
+
+    let axes = RatingAxis::find().all(conn).await?;
+    let chart = RadarChart {
+        size: f64::from(query.size.unwrap_or(DEFAULT_SIZE).clamp(MIN_SIZE, MAX_SIZE)),
+        palette: query.theme.palette(),
+        axes: &axes,
+        series: Series {
+            averages: sauce_axis_averages(conn, sauce.id, None).await?,
+            label: sauce.name,
+        },
+        overlay,
+    };
+    let svg = chart.render().map_err(anyhow::Error::from)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "image/svg+xml"),
+            (header::CACHE_CONTROL, cache_control),
+        ],
+        svg,
+    ))
+}
+
+pub fn router() -> Router {
+    Router::new().route("/sauce/:sauce_id/radar.svg", get(sauce_radar))
+}
@@ -0,0 +1,193 @@
+use std::collections::{HashMap, HashSet};
+
+use entity::prelude::*;
+use sea_orm::{prelude::*, ConnectionTrait, FromQueryResult, QuerySelect};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+/// Name of the rating axis treated as the headline score for a sauce
+pub const OVERALL_AXIS_NAME: &str = "Overall";
+
+pub const HEAT_AXIS_NAME: &str = "Heat";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeatBand {
+    Mild,
+    Medium,
+    Hot,
+    Extreme,
+}
+
+impl HeatBand {
+    /// Band a sauce by how far along the heat axis its mean heat rating sits, from 0.0 to 1.0
+    pub fn from_fraction(fraction: f64) -> Self {
+        if fraction < 0.25 {
+            HeatBand::Mild
+        } else if fraction < 0.5 {
+            HeatBand::Medium
+        } else if fraction < 0.75 {
+            HeatBand::Hot
+        } else {
+            HeatBand::Extreme
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HeatBand::Mild => "mild",
+            HeatBand::Medium => "medium",
+            HeatBand::Hot => "hot",
+            HeatBand::Extreme => "extreme",
+        }
+    }
+}
+
+pub async fn find_axis(
+    conn: &DatabaseConnection,
+    name: &str,
+) -> Result<Option<entity::rating_axis::Model>> {
+    Ok(RatingAxis::find()
+        .filter(entity::rating_axis::Column::Name.eq(name))
+        .one(conn)
+        .await?)
+}
+
+pub fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AxisAverage {
+    pub rating_axis: i32,
+    pub name: String,
+    pub average: f64,
+    pub count: usize,
+}
+
+/// Average the ratings given on each axis, skipping axes with no ratings
+pub fn axis_averages(
+    axes: &[entity::rating_axis::Model],
+    by_axis: &HashMap<i32, Vec<f64>>,
+) -> Vec<AxisAverage> {
+    axes.iter()
+        .filter_map(|axis| {
+            let values = by_axis.get(&axis.id)?;
+            Some(AxisAverage {
+                rating_axis: axis.id,
+                name: axis.name.clone(),
+                average: mean(values)?,
+                count: values.len(),
+            })
+        })
+        .collect()
+}
+
+/// A rating on a single axis, as submitted by a user
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct AxisRating {
+    pub rating_axis: i32,
+    pub rating: f64,
+}
+
+/// Check that each rating is on a known axis and within its range, and that no axis is rated twice
+pub async fn validate_ratings(conn: &impl ConnectionTrait, ratings: &[AxisRating]) -> Result<()> {
+    let axes = RatingAxis::find().all(conn).await?;
+
+    let mut seen = HashSet::new();
+    for rating in ratings {
+        let axis = axes
+            .iter()
+            .find(|axis| axis.id == rating.rating_axis)
+            .ok_or_else(|| {
+                Error::BadRequest(format!("Unknown rating axis {}", rating.rating_axis))
+            })?;
+
+        if !seen.insert(axis.id) {
+            return Err(Error::BadRequest(format!(
+                "{} was rated more than once",
+                axis.name
+            )));
+        }
+
+        if !(axis.min_value..=axis.max_value).contains(&rating.rating) {
+            return Err(Error::BadRequest(format!(
+                "{} ratings must be between {} and {}",
+                axis.name, axis.min_value, axis.max_value
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// A single rating on some axis, flattened together with the review it was given in
+#[derive(Debug, FromQueryResult)]
+pub struct UserSauceRating {
+    pub user: i32,
+    pub sauce: i32,
+    pub rating: f64,
+}
+
+/// Fetch every rating given on the given axis, optionally restricted to a single user. Hidden
+/// reviews aren't included.
+pub async fn axis_ratings(
+    conn: &DatabaseConnection,
+    axis_id: i32,
+    user_id: Option<i32>,
+) -> Result<Vec<UserSauceRating>> {
+    let mut find = ReviewRating::find()
+        .select_only()
+        .column_as(entity::review::Column::User, "user")
+        .column_as(entity::review::Column::Sauce, "sauce")
+        .column(entity::review_rating::Column::Rating)
+        .inner_join(Review)
+        .filter(entity::review_rating::Column::RatingAxis.eq(axis_id))
+        .filter(entity::review::Column::HiddenAt.is_null());
+
+    if let Some(user_id) = user_id {
+        find = find.filter(entity::review::Column::User.eq(user_id));
+    }
+
+    Ok(find.into_model::<UserSauceRating>().all(conn).await?)
+}
+
+#[derive(Debug, FromQueryResult)]
+struct AxisRatingRow {
+    rating_axis: i32,
+    rating: f64,
+}
+
+/// Average rating on each axis for a sauce, from everyone or from a single user. Hidden reviews
+/// aren't counted.
+pub async fn sauce_axis_averages(
+    conn: &DatabaseConnection,
+    sauce_id: i32,
+    user_id: Option<i32>,
+) -> Result<HashMap<i32, f64>> {
+    let mut find = ReviewRating::find()
+        .select_only()
+        .column(entity::review_rating::Column::RatingAxis)
+        .column(entity::review_rating::Column::Rating)
+        .inner_join(Review)
+        .filter(entity::review::Column::Sauce.eq(sauce_id))
+        .filter(entity::review::Column::HiddenAt.is_null());
+    if let Some(user_id) = user_id {
+        find = find.filter(entity::review::Column::User.eq(user_id));
+    }
+
+    let mut by_axis: HashMap<i32, Vec<f64>> = HashMap::new();
+    for row in find.into_model::<AxisRatingRow>().all(conn).await? {
+        by_axis.entry(row.rating_axis).or_default().push(row.rating);
+    }
+
+    Ok(by_axis
+        .into_iter()
+        .filter_map(|(axis, values)| Some((axis, mean(&values)?)))
+        .collect())
+}
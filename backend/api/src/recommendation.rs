@@ -0,0 +1,277 @@
+//! Item-item collaborative filtering over the Overall rating axis.
+//!
+//! Similarities between every pair of sauces are expensive to compute, so they are rebuilt
+//! periodically by a background task and shared with request handlers. Predictions for an
+//! individual user are made at request time from their current ratings, so newly reviewed sauces
+//! drop out of their recommendations immediately.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use axum::{extract::Query, response::IntoResponse, routing::get, Extension, Json, Router};
+use entity::prelude::*;
+use sea_orm::prelude::*;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::{
+    auth::AuthenticatedUser,
+    error::Result,
+    ratings::{axis_ratings, find_axis, UserSauceRating, OVERALL_AXIS_NAME},
+};
+
+/// Maximum number of the user's own ratings used to predict a score for a single sauce
+const NEIGHBOURHOOD_SIZE: usize = 20;
+
+/// Minimum number of users who must have rated both sauces before their similarity is trusted
+const MIN_CO_RATERS: usize = 2;
+
+const DEFAULT_LIMIT: usize = 10;
+
+pub type SharedSimilarityModel = Arc<RwLock<SimilarityModel>>;
+
+#[derive(Debug, Default)]
+pub struct SimilarityModel {
+    /// Mean Overall score of each sauce across all users
+    sauce_means: HashMap<i32, f64>,
+
+    /// Positively similar sauces for each sauce, most similar first
+    neighbours: HashMap<i32, Vec<(i32, f64)>>,
+}
+
+/// Collapse repeat reviews of the same sauce by the same user into a single averaged rating
+fn average_by_user_and_sauce(ratings: &[UserSauceRating]) -> HashMap<(i32, i32), f64> {
+    let mut sums: HashMap<(i32, i32), (f64, usize)> = HashMap::new();
+    for r in ratings {
+        let entry = sums.entry((r.user, r.sauce)).or_default();
+        entry.0 += r.rating;
+        entry.1 += 1;
+    }
+
+    sums.into_iter()
+        .map(|(key, (sum, count))| (key, sum / count as f64))
+        .collect()
+}
+
+#[derive(Default)]
+struct PairAccumulator {
+    dot: f64,
+    norm_a: f64,
+    norm_b: f64,
+    co_raters: usize,
+}
+
+impl SimilarityModel {
+    /// Build the model using adjusted cosine similarity, ie. each rating is taken relative to the
+    /// mean rating of the user that gave it so that harsh and generous reviewers are comparable.
+    pub fn build(ratings: &[UserSauceRating]) -> Self {
+        let ratings = average_by_user_and_sauce(ratings);
+
+        let mut by_user: HashMap<i32, Vec<(i32, f64)>> = HashMap::new();
+        let mut sauce_sums: HashMap<i32, (f64, usize)> = HashMap::new();
+        for (&(user, sauce), &rating) in &ratings {
+            by_user.entry(user).or_default().push((sauce, rating));
+
+            let entry = sauce_sums.entry(sauce).or_default();
+            entry.0 += rating;
+            entry.1 += 1;
+        }
+
+        let sauce_means = sauce_sums
+            .into_iter()
+            .map(|(sauce, (sum, count))| (sauce, sum / count as f64))
+            .collect();
+
+        let mut pairs: HashMap<(i32, i32), PairAccumulator> = HashMap::new();
+        for user_ratings in by_user.values_mut() {
+            let user_mean =
+                user_ratings.iter().map(|(_, r)| r).sum::<f64>() / user_ratings.len() as f64;
+
+            // Sorting means each pair is always keyed with the lowest sauce id first
+            user_ratings.sort_by_key(|(sauce, _)| *sauce);
+
+            for (i, &(sauce_a, rating_a)) in user_ratings.iter().enumerate() {
+                for &(sauce_b, rating_b) in &user_ratings[i + 1..] {
+                    let dev_a = rating_a - user_mean;
+                    let dev_b = rating_b - user_mean;
+
+                    let acc = pairs.entry((sauce_a, sauce_b)).or_default();
+                    acc.dot += dev_a * dev_b;
+                    acc.norm_a += dev_a * dev_a;
+                    acc.norm_b += dev_b * dev_b;
+                    acc.co_raters += 1;
+                }
+            }
+        }
+
+        let mut neighbours: HashMap<i32, Vec<(i32, f64)>> = HashMap::new();
+        for ((sauce_a, sauce_b), acc) in pairs {
+            if acc.co_raters < MIN_CO_RATERS || acc.norm_a == 0.0 || acc.norm_b == 0.0 {
+                continue;
+            }
+
+            let similarity = acc.dot / (acc.norm_a.sqrt() * acc.norm_b.sqrt());
+            if similarity > 0.0 {
+                neighbours
+                    .entry(sauce_a)
+                    .or_default()
+                    .push((sauce_b, similarity));
+                neighbours
+                    .entry(sauce_b)
+                    .or_default()
+                    .push((sauce_a, similarity));
+            }
+        }
+
+        for list in neighbours.values_mut() {
+            list.sort_by(|a, b| b.1.total_cmp(&a.1));
+        }
+
+        Self {
+            sauce_means,
+            neighbours,
+        }
+    }
+
+    /// Predict the Overall score the user would give the sauce, from the similarity weighted
+    /// average of their own ratings of the sauces most similar to it.
+    ///
+    /// Returns `None` if the user hasn't rated any sauces similar to this one.
+    fn predict(&self, user_ratings: &HashMap<i32, f64>, sauce: i32) -> Option<f64> {
+        let neighbours = self.neighbours.get(&sauce)?;
+
+        let (weighted_sum, total_weight) = neighbours
+            .iter()
+            .filter_map(|(other, similarity)| {
+                user_ratings
+                    .get(other)
+                    .map(|rating| (rating * similarity, *similarity))
+            })
+            .take(NEIGHBOURHOOD_SIZE)
+            .fold((0.0, 0.0), |(sum, weight), (r, s)| (sum + r, weight + s));
+
+        if total_weight > 0.0 {
+            Some(weighted_sum / total_weight)
+        } else {
+            None
+        }
+    }
+}
+
+async fn rebuild_model(conn: &DatabaseConnection) -> Result<SimilarityModel> {
+    let ratings = match find_axis(conn, OVERALL_AXIS_NAME).await? {
+        Some(axis) => axis_ratings(conn, axis.id, None).await?,
+        None => {
+            log::warn!("No \"{OVERALL_AXIS_NAME}\" rating axis, can't build recommendations");
+            Vec::new()
+        }
+    };
+
+    Ok(SimilarityModel::build(&ratings))
+}
+
+/// Spawn a task which rebuilds the shared model immediately, and then again every `period`
+pub fn spawn_refresh_task(
+    conn: DatabaseConnection,
+    model: SharedSimilarityModel,
+    period: Duration,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            match rebuild_model(&conn).await {
+                Ok(new_model) => {
+                    log::debug!(
+                        "Rebuilt recommendation model covering {} sauces",
+                        new_model.sauce_means.len()
+                    );
+                    *model.write().await = new_model;
+                }
+                Err(e) => log::error!("Failed to rebuild recommendation model: {e:?}"),
+            }
+        }
+    });
+}
+
+#[derive(Debug, Deserialize)]
+struct RecommendationsQuery {
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct Recommendation {
+    #[serde(flatten)]
+    sauce: entity::sauce::Model,
+    predicted_overall: f64,
+
+    /// False if the user hasn't rated anything similar, in which case the prediction is just the
+    /// community mean score
+    personalised: bool,
+}
+
+async fn recommendations(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(model): Extension<SharedSimilarityModel>,
+    Query(query): Query<RecommendationsQuery>,
+) -> Result<impl IntoResponse> {
+    let axis = match find_axis(conn, OVERALL_AXIS_NAME).await? {
+        Some(axis) => axis,
+        None => return Ok(Json(Vec::new())),
+    };
+
+    let user_ratings: HashMap<i32, f64> =
+        average_by_user_and_sauce(&axis_ratings(conn, axis.id, Some(auth.user_id)).await?)
+            .into_iter()
+            .map(|((_user, sauce), rating)| (sauce, rating))
+            .collect();
+
+    let reviewed: HashSet<i32> = Review::find()
+        .filter(entity::review::Column::User.eq(auth.user_id))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|review| review.sauce)
+        .collect();
+
+    let candidates = Sauce::find()
+        .filter(entity::sauce::Column::HiddenAt.is_null())
+        .all(conn)
+        .await?;
+
+    let model = model.read().await;
+    let mut recommendations: Vec<_> = candidates
+        .into_iter()
+        .filter(|sauce| !reviewed.contains(&sauce.id))
+        .filter_map(|sauce| {
+            let (predicted_overall, personalised) = match model.predict(&user_ratings, sauce.id) {
+                Some(prediction) => (prediction, true),
+                None => (*model.sauce_means.get(&sauce.id)?, false),
+            };
+
+            Some(Recommendation {
+                sauce,
+                predicted_overall,
+                personalised,
+            })
+        })
+        .collect();
+    drop(model);
+
+    recommendations.sort_by(|a, b| {
+        b.personalised
+            .cmp(&a.personalised)
+            .then(b.predicted_overall.total_cmp(&a.predicted_overall))
+    });
+    recommendations.truncate(query.limit.unwrap_or(DEFAULT_LIMIT));
+
+    Ok(Json(recommendations))
+}
+
+pub fn router() -> Router {
+    Router::new().route("/recommendations", get(recommendations))
+}
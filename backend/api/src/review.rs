@@ -0,0 +1,500 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query},
+    response::IntoResponse,
+    routing::{get, post, put},
+    Extension, Json, Router,
+};
+use chrono::Utc;
+use entity::{
+    prelude::*,
+    sea_orm_active_enums::{ModerationTarget, ProfilePrivacy},
+};
+use sea_orm::{
+    prelude::*,
+    sea_query::{Expr, Query as SubQuery},
+    Condition, ConnectionTrait, FromQueryResult, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    activity::{record_activity, NewActivity},
+    auth::AuthenticatedUser,
+    batch::validate_batch,
+    block::{ensure_not_blocked, ignored_users},
+    error::{Error, Result},
+    moderation::hold_for_moderation,
+    pagination::{fetch_page, page_from_vec, PageParams},
+    profile::ensure_profile_visible,
+    ratings::{validate_ratings, AxisRating},
+    review_image::{review_images, ReviewImageResponse},
+    screening::Screening,
+};
+
+/// z-score for a 95% confidence level
+const WILSON_Z: f64 = 1.96;
+
+#[derive(Debug, Serialize)]
+pub struct RatingResponse {
+    pub rating_axis: i32,
+    pub rating: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewResponse {
+    #[serde(flatten)]
+    pub review: entity::review::Model,
+    pub username: String,
+    pub ratings: Vec<RatingResponse>,
+    pub comment_count: i64,
+    pub helpful_votes: i64,
+    pub unhelpful_votes: i64,
+    pub helpfulness: f64,
+    pub images: Vec<ReviewImageResponse>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct CommentCount {
+    review: i32,
+    count: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct VoteCount {
+    review: i32,
+    helpful: bool,
+    count: i64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VoteTally {
+    pub helpful: i64,
+    pub unhelpful: i64,
+}
+
+impl VoteTally {
+    /// The lower bound of the Wilson score interval for the proportion of helpful votes. A review
+    /// needs a good number of votes before it can rank highly, so one with a single helpful vote
+    /// doesn't beat one voted helpful by nine people out of ten.
+    pub fn helpfulness(&self) -> f64 {
+        let total = (self.helpful + self.unhelpful) as f64;
+        if total == 0.0 {
+            return 0.0;
+        }
+
+        let p = self.helpful as f64 / total;
+        let z2 = WILSON_Z * WILSON_Z;
+
+        (p + z2 / (2.0 * total) - WILSON_Z * ((p * (1.0 - p) + z2 / (4.0 * total)) / total).sqrt())
+            / (1.0 + z2 / total)
+    }
+}
+
+/// Count the helpful and unhelpful votes on each review
+pub async fn vote_tallies(
+    conn: &DatabaseConnection,
+    review_ids: Vec<i32>,
+) -> Result<HashMap<i32, VoteTally>> {
+    use entity::review_vote::Column;
+
+    let mut tallies: HashMap<i32, VoteTally> = HashMap::new();
+    for count in ReviewVote::find()
+        .select_only()
+        .column(Column::Review)
+        .column(Column::Helpful)
+        .column_as(Expr::col(Column::User).count(), "count")
+        .filter(Column::Review.is_in(review_ids))
+        .group_by(Column::Review)
+        .group_by(Column::Helpful)
+        .into_model::<VoteCount>()
+        .all(conn)
+        .await?
+    {
+        let tally = tallies.entry(count.review).or_default();
+        match count.helpful {
+            true => tally.helpful = count.count,
+            false => tally.unhelpful = count.count,
+        }
+    }
+
+    Ok(tallies)
+}
+
+/// Attach the ratings and reviewer's username to each review, preserving the order of `reviews`
+pub async fn review_responses(
+    conn: &DatabaseConnection,
+    reviews: Vec<entity::review::Model>,
+) -> Result<Vec<ReviewResponse>> {
+    let review_ids: Vec<i32> = reviews.iter().map(|review| review.id).collect();
+    let user_ids: Vec<i32> = reviews.iter().map(|review| review.user).collect();
+
+    let mut ratings: HashMap<i32, Vec<RatingResponse>> = HashMap::new();
+    for rating in ReviewRating::find()
+        .filter(entity::review_rating::Column::Review.is_in(review_ids.clone()))
+        .all(conn)
+        .await?
+    {
+        ratings
+            .entry(rating.review)
+            .or_default()
+            .push(RatingResponse {
+                rating_axis: rating.rating_axis,
+                rating: rating.rating,
+            });
+    }
+
+    let usernames: HashMap<i32, String> = User::find()
+        .filter(entity::user::Column::Id.is_in(user_ids))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user.username))
+        .collect();
+
+    let comment_counts: HashMap<i32, i64> = ReviewComment::find()
+        .select_only()
+        .column(entity::review_comment::Column::Review)
+        .column_as(
+            Expr::col(entity::review_comment::Column::Id).count(),
+            "count",
+        )
+        .filter(entity::review_comment::Column::Review.is_in(review_ids.clone()))
+        .filter(entity::review_comment::Column::DeletedAt.is_null())
+        .filter(entity::review_comment::Column::HiddenAt.is_null())
+        .group_by(entity::review_comment::Column::Review)
+        .into_model::<CommentCount>()
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|count| (count.review, count.count))
+        .collect();
+
+    let tallies = vote_tallies(conn, review_ids.clone()).await?;
+    let mut images = review_images(conn, review_ids).await?;
+
+    Ok(reviews
+        .into_iter()
+        .map(|review| {
+            let tally = tallies.get(&review.id).copied().unwrap_or_default();
+            ReviewResponse {
+                comment_count: comment_counts.get(&review.id).copied().unwrap_or(0),
+                helpful_votes: tally.helpful,
+                unhelpful_votes: tally.unhelpful,
+                helpfulness: tally.helpfulness(),
+                images: images.remove(&review.id).unwrap_or_default(),
+                username: usernames.get(&review.user).cloned().unwrap_or_default(),
+                ratings: ratings.remove(&review.id).unwrap_or_default(),
+                review,
+            }
+        })
+        .collect())
+}
+
+/// Find a review whose author's profile the viewer is allowed to see
+pub async fn find_visible_review(
+    conn: &DatabaseConnection,
+    viewer: Option<&AuthenticatedUser>,
+    review_id: i32,
+) -> Result<entity::review::Model> {
+    let review = Review::find_by_id(review_id)
+        .filter(entity::review::Column::HiddenAt.is_null())
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    let reviewer = User::find_by_id(review.user)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+    ensure_profile_visible(viewer, &reviewer)?;
+
+    Ok(review)
+}
+
+/// Create a review along with its ratings, which should already have been validated, and let the
+/// reviewer's followers know about it
+pub async fn insert_review(
+    conn: &impl ConnectionTrait,
+    review: entity::review::ActiveModel,
+    ratings: &[AxisRating],
+) -> Result<entity::review::Model> {
+    let review = review.insert(conn).await?;
+
+    for rating in ratings {
+        entity::review_rating::ActiveModel {
+            review: Set(review.id),
+            rating_axis: Set(rating.rating_axis),
+            rating: Set(rating.rating),
+            ..Default::default()
+        }
+        .insert(conn)
+        .await?;
+    }
+
+    record_activity(
+        conn,
+        Some(review.user),
+        NewActivity::ReviewCreated {
+            review: review.id,
+            sauce: review.sauce,
+        },
+    )
+    .await?;
+
+    Ok(review)
+}
+
+/// Delete reviews along with their ratings
+pub async fn delete_reviews(conn: &impl ConnectionTrait, review_ids: Vec<i32>) -> Result<()> {
+    ReviewRating::delete_many()
+        .filter(entity::review_rating::Column::Review.is_in(review_ids.clone()))
+        .exec(conn)
+        .await?;
+
+    Review::delete_many()
+        .filter(entity::review::Column::Id.is_in(review_ids))
+        .exec(conn)
+        .await?;
+
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+struct NewReview {
+    sauce: i32,
+    text: Option<String>,
+    ratings: Vec<AxisRating>,
+    /// The batch of the sauce that was tasted, if the reviewer knows it
+    #[serde(default)]
+    batch: Option<i32>,
+}
+
+async fn review_insert(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(screening): Extension<Screening>,
+    Json(new): Json<NewReview>,
+) -> Result<impl IntoResponse> {
+    Sauce::find_by_id(new.sauce)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    validate_ratings(conn, &new.ratings).await?;
+    validate_batch(conn, new.sauce, new.batch).await?;
+
+    let held = screening
+        .check(conn, auth.user_id, new.text.as_deref())
+        .await?;
+
+    let txn = conn.begin().await?;
+    let review = insert_review(
+        &txn,
+        entity::review::ActiveModel {
+            sauce: Set(new.sauce),
+            user: Set(auth.user_id),
+            timestamp: Set(Utc::now()),
+            text: Set(new.text),
+            batch: Set(new.batch),
+            ..Default::default()
+        },
+        &new.ratings,
+    )
+    .await?;
+    if let Some(reason) = &held {
+        hold_for_moderation(&txn, ModerationTarget::Review, review.id, &reason.message).await?;
+    }
+    txn.commit().await?;
+
+    let review = Review::find_by_id(review.id)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    let mut responses = review_responses(conn, vec![review]).await?;
+    Ok(Json(responses.pop()))
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ReviewSort {
+    #[default]
+    Recent,
+    Helpful,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewQuery {
+    #[serde(default)]
+    sort: ReviewSort,
+}
+
+/// Reviews of a sauce by everyone whose profile the viewer is allowed to see, newest first by
+/// default
+async fn sauce_reviews(
+    auth: Option<AuthenticatedUser>,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(sauce_id): Path<i32>,
+    Query(query): Query<ReviewQuery>,
+    Query(page_params): Query<PageParams>,
+) -> Result<impl IntoResponse> {
+    use entity::review::Column;
+
+    Sauce::find_by_id(sauce_id)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    let mut hidden_privacy = vec![ProfilePrivacy::Private];
+    if auth.is_none() {
+        hidden_privacy.push(ProfilePrivacy::LoggedIn);
+    }
+    let mut hidden = Condition::all().add(
+        Condition::any()
+            .add(entity::user::Column::Privacy.is_in(hidden_privacy))
+            .add(entity::user::Column::HiddenAt.is_not_null()),
+    );
+    if let Some(auth) = &auth {
+        hidden = hidden.add(entity::user::Column::Id.ne(auth.user_id));
+    }
+    let hidden_users = SubQuery::select()
+        .column(entity::user::Column::Id)
+        .from(User)
+        .cond_where(hidden)
+        .to_owned();
+
+    let mut select = Review::find()
+        .filter(Column::Sauce.eq(sauce_id))
+        .filter(Column::User.not_in_subquery(hidden_users))
+        .filter(Column::HiddenAt.is_null());
+    if let Some(auth) = &auth {
+        select = select.filter(Column::User.not_in_subquery(ignored_users(auth.user_id)));
+    }
+    let select = select
+        .order_by_desc(Column::Timestamp)
+        .order_by_desc(Column::Id);
+
+    let mut page = match query.sort {
+        ReviewSort::Recent => fetch_page(conn, select, &page_params).await?,
+        ReviewSort::Helpful => {
+            // Helpfulness isn't something SQLite can work out, so every review of the sauce is
+            // ranked here instead. Ties keep the newest first.
+            let mut reviews = select.all(conn).await?;
+            let tallies = vote_tallies(conn, reviews.iter().map(|r| r.id).collect()).await?;
+            let helpfulness = |review: &entity::review::Model| {
+                tallies
+                    .get(&review.id)
+                    .map(VoteTally::helpfulness)
+                    .unwrap_or(0.0)
+            };
+            reviews.sort_by(|a, b| helpfulness(b).total_cmp(&helpfulness(a)));
+
+            page_from_vec(reviews, &page_params)?
+        }
+    };
+    let reviews = std::mem::take(&mut page.items);
+
+    Ok(Json(
+        page.with_items(review_responses(conn, reviews).await?),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+struct VoteParams {
+    helpful: bool,
+}
+
+/// Vote on whether a review was helpful, replacing any earlier vote on it
+async fn vote_set(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(review_id): Path<i32>,
+    Json(params): Json<VoteParams>,
+) -> Result<impl IntoResponse> {
+    let review = find_visible_review(conn, Some(&auth), review_id).await?;
+
+    if review.user == auth.user_id {
+        return Err(Error::BadRequest(
+            "You can't vote on your own review".to_string(),
+        ));
+    }
+    ensure_not_blocked(conn, auth.user_id, review.user).await?;
+
+    match ReviewVote::find_by_id((review.id, auth.user_id))
+        .one(conn)
+        .await?
+    {
+        Some(vote) => {
+            let mut vote: entity::review_vote::ActiveModel = vote.into();
+            vote.helpful = Set(params.helpful);
+            vote.update(conn).await?;
+        }
+        None => {
+            entity::review_vote::ActiveModel {
+                review: Set(review.id),
+                user: Set(auth.user_id),
+                helpful: Set(params.helpful),
+                created_at: Set(Utc::now()),
+            }
+            .insert(conn)
+            .await?;
+        }
+    }
+
+    let mut responses = review_responses(conn, vec![review]).await?;
+    Ok(Json(responses.pop()))
+}
+
+async fn vote_delete(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(review_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let result = ReviewVote::delete_by_id((review_id, auth.user_id))
+        .exec(conn)
+        .await?;
+
+    if result.rows_affected == 0 {
+        return Err(Error::NotFound);
+    }
+
+    Ok(Json(()))
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/review", post(review_insert))
+        .route("/review/:review_id/vote", put(vote_set).delete(vote_delete))
+        .route("/sauce/:sauce_id/reviews", get(sauce_reviews))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VoteTally;
+
+    fn helpfulness(helpful: i64, unhelpful: i64) -> f64 {
+        VoteTally { helpful, unhelpful }.helpfulness()
+    }
+
+    #[test]
+    fn no_votes_scores_zero() {
+        assert_eq!(helpfulness(0, 0), 0.0);
+    }
+
+    #[test]
+    fn single_vote_is_heavily_discounted() {
+        assert!((helpfulness(1, 0) - 0.2065).abs() < 0.0001);
+        assert!(helpfulness(0, 1).abs() < 1e-9);
+        assert!(helpfulness(1, 0) < helpfulness(9, 1));
+    }
+
+    #[test]
+    fn more_votes_tighten_the_bound() {
+        let few = helpfulness(4, 1);
+        let many = helpfulness(400, 100);
+        assert!(few < many);
+        assert!(many < 0.8);
+        assert!((many - 0.7627).abs() < 0.0001);
+    }
+}
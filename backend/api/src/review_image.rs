@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Multipart, Path},
+    http::header,
+    response::IntoResponse,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use chrono::{DateTime, Utc};
+use entity::prelude::*;
+use rand::Rng;
+use sea_orm::{prelude::*, ConnectionTrait, PaginatorTrait, QueryOrder, Set};
+use serde::Serialize;
+
+use crate::{
+    auth::AuthenticatedUser,
+    error::{Error, Result},
+    images::{process_image, read_upload, ImageSettings, ImageSize, CONTENT_TYPE},
+    review::find_visible_review,
+    storage::{delete_stored_files, SharedStorage},
+    API_PREFIX,
+};
+
+const MAX_IMAGES_PER_REVIEW: usize = 6;
+
+/// Stored images never change, so clients can hang on to them. They're only cached privately
+/// because the review they belong to might not be public.
+const CACHE_CONTROL: &str = "private, max-age=31536000, immutable";
+
+#[derive(Debug, Serialize)]
+pub struct ReviewImageResponse {
+    pub id: i32,
+    pub review: i32,
+    pub width: i32,
+    pub height: i32,
+    pub url: String,
+    pub thumbnail_url: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<entity::review_image::Model> for ReviewImageResponse {
+    fn from(image: entity::review_image::Model) -> Self {
+        Self {
+            id: image.id,
+            review: image.review,
+            width: image.width,
+            height: image.height,
+            url: format!("{API_PREFIX}/review_image/{}", image.id),
+            thumbnail_url: format!("{API_PREFIX}/review_image/{}/thumbnail", image.id),
+            created_at: image.created_at,
+        }
+    }
+}
+
+/// The images attached to each review, in the order they were uploaded
+pub async fn review_images(
+    conn: &DatabaseConnection,
+    review_ids: Vec<i32>,
+) -> Result<HashMap<i32, Vec<ReviewImageResponse>>> {
+    let mut images: HashMap<i32, Vec<ReviewImageResponse>> = HashMap::new();
+    for image in ReviewImage::find()
+        .filter(entity::review_image::Column::Review.is_in(review_ids))
+        .order_by_asc(entity::review_image::Column::Id)
+        .all(conn)
+        .await?
+    {
+        images.entry(image.review).or_default().push(image.into());
+    }
+
+    Ok(images)
+}
+
+/// Storage keys of every file belonging to the reviews' images. The rows go when their review is
+/// deleted, but the files have to be cleaned up separately once that's been committed.
+pub async fn review_image_keys(
+    conn: &impl ConnectionTrait,
+    review_ids: Vec<i32>,
+) -> Result<Vec<String>> {
+    Ok(ReviewImage::find()
+        .filter(entity::review_image::Column::Review.is_in(review_ids))
+        .all(conn)
+        .await?
+        .into_iter()
+        .flat_map(|image| [image.storage_key, image.thumbnail_key])
+        .collect())
+}
+
+fn new_storage_key(review_id: i32) -> String {
+    let id: u128 = rand::thread_rng().gen();
+    format!("reviews/{review_id}/{id:032x}")
+}
+
+/// Attach photos to one of the caller's reviews. Every `image` field in the form is stored.
+async fn upload_images(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(storage): Extension<SharedStorage>,
+    Extension(settings): Extension<ImageSettings>,
+    Path(review_id): Path<i32>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse> {
+    let review = Review::find_by_id(review_id)
+        .filter(entity::review::Column::User.eq(auth.user_id))
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    let mut image_count = ReviewImage::find()
+        .filter(entity::review_image::Column::Review.eq(review.id))
+        .count(conn)
+        .await?;
+
+    let mut uploaded = Vec::new();
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| Error::BadRequest(e.to_string()))?
+    {
+        if field.name() != Some("image") {
+            continue;
+        }
+        if image_count >= MAX_IMAGES_PER_REVIEW {
+            return Err(Error::BadRequest(format!(
+                "A review can have at most {MAX_IMAGES_PER_REVIEW} images"
+            )));
+        }
+
+        let data = read_upload(field, settings.max_upload_bytes).await?;
+        let [full, thumbnail] = tokio::task::spawn_blocking(move || {
+            process_image(&data, [ImageSize::Full, ImageSize::Thumbnail])
+        })
+        .await
+        .map_err(anyhow::Error::from)??;
+
+        let key = new_storage_key(review.id);
+        let storage_key = format!("{key}_{}.webp", ImageSize::Full.name());
+        let thumbnail_key = format!("{key}_{}.webp", ImageSize::Thumbnail.name());
+        let stored: Result<_> = async {
+            storage.put(&storage_key, full.data).await?;
+            storage.put(&thumbnail_key, thumbnail.data).await?;
+
+            Ok(entity::review_image::ActiveModel {
+                review: Set(review.id),
+                storage_key: Set(storage_key.clone()),
+                thumbnail_key: Set(thumbnail_key.clone()),
+                content_type: Set(CONTENT_TYPE.to_string()),
+                width: Set(full.width as i32),
+                height: Set(full.height as i32),
+                created_at: Set(Utc::now()),
+                ..Default::default()
+            }
+            .insert(conn)
+            .await?)
+        }
+        .await;
+        let image = match stored {
+            Ok(image) => image,
+            Err(e) => {
+                delete_stored_files(&storage, &[storage_key, thumbnail_key]).await;
+                return Err(e);
+            }
+        };
+
+        image_count += 1;
+        uploaded.push(ReviewImageResponse::from(image));
+    }
+
+    if uploaded.is_empty() {
+        return Err(Error::BadRequest(
+            "Upload an image in the \"image\" field".to_string(),
+        ));
+    }
+
+    Ok(Json(uploaded))
+}
+
+async fn list_images(
+    auth: Option<AuthenticatedUser>,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(review_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let review = find_visible_review(conn, auth.as_ref(), review_id).await?;
+    let mut images = review_images(conn, vec![review.id]).await?;
+
+    Ok(Json(images.remove(&review.id).unwrap_or_default()))
+}
+
+/// An image, as long as the viewer can see the review it's attached to
+async fn find_visible_image(
+    conn: &DatabaseConnection,
+    viewer: Option<&AuthenticatedUser>,
+    image_id: i32,
+) -> Result<entity::review_image::Model> {
+    let image = ReviewImage::find_by_id(image_id)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+    find_visible_review(conn, viewer, image.review).await?;
+
+    Ok(image)
+}
+
+async fn serve_file(
+    storage: &SharedStorage,
+    key: &str,
+    content_type: String,
+) -> Result<impl IntoResponse> {
+    let data = storage.get(key).await?.ok_or(Error::NotFound)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::CACHE_CONTROL, CACHE_CONTROL.to_string()),
+        ],
+        data,
+    ))
+}
+
+async fn image_full(
+    auth: Option<AuthenticatedUser>,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(storage): Extension<SharedStorage>,
+    Path(image_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let image = find_visible_image(conn, auth.as_ref(), image_id).await?;
+    serve_file(&storage, &image.storage_key, image.content_type).await
+}
+
+async fn image_thumbnail(
+    auth: Option<AuthenticatedUser>,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(storage): Extension<SharedStorage>,
+    Path(image_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let image = find_visible_image(conn, auth.as_ref(), image_id).await?;
+    serve_file(&storage, &image.thumbnail_key, image.content_type).await
+}
+
+async fn delete_image(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(storage): Extension<SharedStorage>,
+    Path(image_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let (image, review) = ReviewImage::find_by_id(image_id)
+        .find_also_related(Review)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+    if review.map(|review| review.user) != Some(auth.user_id) {
+        return Err(Error::NotFound);
+    }
+
+    ReviewImage::delete_by_id(image.id).exec(conn).await?;
+    delete_stored_files(&storage, &[image.storage_key, image.thumbnail_key]).await;
+
+    Ok(Json(()))
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/review/:review_id/images", get(list_images))
+        .route(
+            "/review_image/:image_id",
+            get(image_full).delete(delete_image),
+        )
+        .route("/review_image/:image_id/thumbnail", get(image_thumbnail))
+}
+
+/// Uploads are kept apart from the other routes since they take a lot longer to handle
+pub fn upload_router() -> Router {
+    Router::new().route("/review/:review_id/images", post(upload_images))
+}
@@ -1,24 +1,76 @@
 use axum::{extract::Query, response::IntoResponse, routing::get, Extension, Json, Router};
-use sea_orm::prelude::*;
+use sea_orm::{prelude::*, sea_query::Query as SubQuery, Set};
 
-use entity::prelude::*;
-use serde::Deserialize;
+use entity::{prelude::*, sea_orm_active_enums::ShelfStatus};
+use serde::{Deserialize, Serialize};
 
-use crate::error::{Error, Result};
+use crate::{
+    activity::{record_activity, NewActivity},
+    auth::AuthenticatedUser,
+    catalog_image::{sauce_covers, CatalogImageResponse},
+    error::{Error, Result},
+};
+
+const MAX_SAUCE_NAME_LENGTH: usize = 100;
 
 #[derive(Debug, Deserialize)]
 struct SaucesListQuery {
     pub manufacturer_id: Option<i32>,
     pub sauce_id: Option<i32>,
+
+    /// Only sauces that are (or aren't) on the caller's shelf
+    pub on_shelf: Option<bool>,
+
+    /// Restricts `on_shelf` to shelf entries with this status
+    pub shelf_status: Option<ShelfStatus>,
+
+    /// Only sauces the caller has (or hasn't) reviewed
+    pub not_reviewed: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SauceResponse {
+    #[serde(flatten)]
+    pub sauce: entity::sauce::Model,
+    pub image: Option<CatalogImageResponse>,
+}
+
+/// Attach each sauce's cover image, preserving the order of `sauces`
+pub async fn sauce_responses(
+    conn: &DatabaseConnection,
+    sauces: Vec<entity::sauce::Model>,
+) -> Result<Vec<SauceResponse>> {
+    let mut covers = sauce_covers(conn, sauces.iter().map(|sauce| sauce.id).collect()).await?;
+
+    Ok(sauces
+        .into_iter()
+        .map(|sauce| SauceResponse {
+            image: covers.remove(&sauce.id),
+            sauce,
+        })
+        .collect())
+}
+
+/// A sauce that hasn't been hidden by a moderator
+pub async fn find_visible_sauce(
+    conn: &DatabaseConnection,
+    sauce_id: i32,
+) -> Result<entity::sauce::Model> {
+    Sauce::find_by_id(sauce_id)
+        .filter(entity::sauce::Column::HiddenAt.is_null())
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)
 }
 
 async fn sauce_list(
+    auth: Option<AuthenticatedUser>,
     Extension(ref conn): Extension<DatabaseConnection>,
     Query(query): Query<SaucesListQuery>,
 ) -> Result<impl IntoResponse> {
     use entity::sauce::Column::*;
 
-    let mut find = Sauce::find();
+    let mut find = Sauce::find().filter(HiddenAt.is_null());
     if let Some(manufacturer_id) = query.manufacturer_id {
         find = find.filter(Manufacturer.eq(manufacturer_id));
     }
@@ -26,9 +78,108 @@ async fn sauce_list(
         find = find.filter(Id.eq(sauce_id));
     }
 
-    find.all(conn).await.map(|x| Json(x)).map_err(Error::from)
+    if query.on_shelf.is_some() || query.shelf_status.is_some() {
+        let user_id = auth.as_ref().ok_or(Error::Unauthorized)?.user_id;
+
+        let mut shelved = SubQuery::select();
+        shelved
+            .column(entity::shelf_entry::Column::Sauce)
+            .from(ShelfEntry)
+            .and_where(entity::shelf_entry::Column::User.eq(user_id));
+        if let Some(status) = query.shelf_status {
+            shelved.and_where(entity::shelf_entry::Column::Status.eq(status));
+        }
+
+        find = match query.on_shelf.unwrap_or(true) {
+            true => find.filter(Id.in_subquery(shelved)),
+            false => find.filter(Id.not_in_subquery(shelved)),
+        };
+    }
+
+    if let Some(not_reviewed) = query.not_reviewed {
+        let user_id = auth.as_ref().ok_or(Error::Unauthorized)?.user_id;
+
+        let reviewed = SubQuery::select()
+            .column(entity::review::Column::Sauce)
+            .from(Review)
+            .and_where(entity::review::Column::User.eq(user_id))
+            .to_owned();
+
+        find = match not_reviewed {
+            true => find.filter(Id.not_in_subquery(reviewed)),
+            false => find.filter(Id.in_subquery(reviewed)),
+        };
+    }
+
+    let sauces = find.all(conn).await?;
+    Ok(Json(sauce_responses(conn, sauces).await?))
+}
+
+#[derive(Deserialize, Debug)]
+struct NewSauce {
+    pub name: String,
+    pub manufacturer: i32,
+}
+
+async fn sauce_insert(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Json(new): Json<NewSauce>,
+) -> Result<impl IntoResponse> {
+    use entity::sauce::Column;
+
+    let name = new.name.trim().to_string();
+    if name.is_empty() {
+        return Err(Error::BadRequest(
+            "Sauce name must not be empty".to_string(),
+        ));
+    }
+    if name.chars().count() > MAX_SAUCE_NAME_LENGTH {
+        return Err(Error::BadRequest(format!(
+            "Sauce name must be at most {MAX_SAUCE_NAME_LENGTH} characters"
+        )));
+    }
+
+    Manufacturer::find_by_id(new.manufacturer)
+        .filter(entity::manufacturer::Column::HiddenAt.is_null())
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    if let Some(existing) = Sauce::find()
+        .filter(Column::Name.eq(name.clone()))
+        .filter(Column::Manufacturer.eq(new.manufacturer))
+        .one(conn)
+        .await?
+    {
+        // A sauce hidden by a moderator can't be brought back by adding it again
+        if existing.hidden_at.is_some() {
+            return Err(Error::NotFound);
+        }
+        return Ok(Json(existing));
+    }
+
+    let inserted = entity::sauce::ActiveModel {
+        name: Set(name),
+        manufacturer: Set(new.manufacturer),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await?;
+
+    record_activity(
+        conn,
+        Some(auth.user_id),
+        NewActivity::SauceAdded {
+            sauce: inserted.id,
+            manufacturer: inserted.manufacturer,
+        },
+    )
+    .await?;
+
+    Ok(Json(inserted))
 }
 
 pub fn router() -> Router {
-    Router::new().route("/sauce", get(sauce_list))
+    Router::new().route("/sauce", get(sauce_list).put(sauce_insert))
 }
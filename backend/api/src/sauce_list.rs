@@ -0,0 +1,647 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::{
+    extract::{Path, Query},
+    response::IntoResponse,
+    routing::{get, post, put},
+    Extension, Json, Router,
+};
+use chrono::Utc;
+use entity::{prelude::*, sea_orm_active_enums::ProfilePrivacy};
+use sea_orm::{
+    prelude::*, sea_query::Expr, ConnectionTrait, FromQueryResult, QueryOrder, QuerySelect, Set,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    activity::{record_activity, NewActivity},
+    auth::AuthenticatedUser,
+    block::{ensure_not_ignored, ignored_users},
+    error::{Error, Result},
+    pagination::{fetch_page, PageParams},
+    profile::{ensure_profile_visible, find_user_by_username},
+    sauce::find_visible_sauce,
+    screening::{Screening, ScreeningReason},
+};
+
+const MAX_LIST_NAME_LENGTH: usize = 100;
+
+/// Orders by the number of times a list has been forked
+const FORK_COUNT_SQL: &str =
+    "(SELECT COUNT(*) FROM sauce_list AS fork WHERE fork.forked_from = sauce_list.id)";
+
+#[derive(Debug, Serialize)]
+struct ListSummary {
+    #[serde(flatten)]
+    list: entity::sauce_list::Model,
+    username: String,
+    entry_count: i64,
+    fork_count: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct IdCount {
+    id: i32,
+    count: i64,
+}
+
+fn into_count_map(counts: Vec<IdCount>) -> HashMap<i32, i64> {
+    counts.into_iter().map(|c| (c.id, c.count)).collect()
+}
+
+/// Attach the owner's username and the entry and fork counts to each list, preserving the order of
+/// `lists`
+async fn list_summaries(
+    conn: &DatabaseConnection,
+    lists: Vec<entity::sauce_list::Model>,
+) -> Result<Vec<ListSummary>> {
+    let list_ids: Vec<i32> = lists.iter().map(|list| list.id).collect();
+    let user_ids: Vec<i32> = lists.iter().map(|list| list.user).collect();
+
+    let usernames: HashMap<i32, String> = User::find()
+        .filter(entity::user::Column::Id.is_in(user_ids))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user.username))
+        .collect();
+
+    let entry_counts = into_count_map(
+        SauceListEntry::find()
+            .select_only()
+            .column_as(entity::sauce_list_entry::Column::List, "id")
+            .column_as(
+                Expr::col(entity::sauce_list_entry::Column::Id).count(),
+                "count",
+            )
+            .filter(entity::sauce_list_entry::Column::List.is_in(list_ids.clone()))
+            .group_by(entity::sauce_list_entry::Column::List)
+            .into_model::<IdCount>()
+            .all(conn)
+            .await?,
+    );
+
+    let fork_counts = into_count_map(
+        SauceList::find()
+            .select_only()
+            .column_as(entity::sauce_list::Column::ForkedFrom, "id")
+            .column_as(Expr::col(entity::sauce_list::Column::Id).count(), "count")
+            .filter(entity::sauce_list::Column::ForkedFrom.is_in(list_ids))
+            .group_by(entity::sauce_list::Column::ForkedFrom)
+            .into_model::<IdCount>()
+            .all(conn)
+            .await?,
+    );
+
+    Ok(lists
+        .into_iter()
+        .map(|list| ListSummary {
+            username: usernames.get(&list.user).cloned().unwrap_or_default(),
+            entry_count: entry_counts.get(&list.id).copied().unwrap_or(0),
+            fork_count: fork_counts.get(&list.id).copied().unwrap_or(0),
+            list,
+        })
+        .collect())
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ListSort {
+    #[default]
+    Popular,
+    Recent,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListQuery {
+    #[serde(default)]
+    sort: ListSort,
+}
+
+/// Public lists from users with public profiles, most forked first by default
+async fn public_lists(
+    auth: Option<AuthenticatedUser>,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Query(query): Query<ListQuery>,
+    Query(page_params): Query<PageParams>,
+) -> Result<impl IntoResponse> {
+    use entity::sauce_list::Column;
+
+    let public_users = sea_orm::sea_query::Query::select()
+        .column(entity::user::Column::Id)
+        .from(User)
+        .and_where(entity::user::Column::Privacy.eq(ProfilePrivacy::Public))
+        .to_owned();
+
+    let mut select = SauceList::find()
+        .filter(Column::Public.eq(true))
+        .filter(Column::User.in_subquery(public_users));
+    if let Some(auth) = &auth {
+        select = select.filter(Column::User.not_in_subquery(ignored_users(auth.user_id)));
+    }
+    if let ListSort::Popular = query.sort {
+        select = select.order_by_desc(Expr::cust(FORK_COUNT_SQL));
+    }
+    let select = select
+        .order_by_desc(Column::UpdatedAt)
+        .order_by_desc(Column::Id);
+
+    let mut page = fetch_page(conn, select, &page_params).await?;
+    let lists = std::mem::take(&mut page.items);
+
+    Ok(Json(page.with_items(list_summaries(conn, lists).await?)))
+}
+
+/// A user's lists. Their private lists are only included when they're the one asking.
+async fn user_lists(
+    auth: Option<AuthenticatedUser>,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(username): Path<String>,
+) -> Result<impl IntoResponse> {
+    use entity::sauce_list::Column;
+
+    let user = find_user_by_username(conn, &username).await?;
+    ensure_profile_visible(auth.as_ref(), &user)?;
+    ensure_not_ignored(conn, auth.as_ref(), user.id).await?;
+
+    let is_self = auth.is_some_and(|auth| auth.user_id == user.id);
+
+    let mut select = SauceList::find()
+        .filter(Column::User.eq(user.id))
+        .order_by_desc(Column::UpdatedAt);
+    if !is_self {
+        select = select.filter(Column::Public.eq(true));
+    }
+
+    let lists = select.all(conn).await?;
+    Ok(Json(list_summaries(conn, lists).await?))
+}
+
+/// Find a list the viewer is allowed to see. Private lists are hidden from everyone but their
+/// owner.
+async fn find_visible_list(
+    conn: &DatabaseConnection,
+    viewer: Option<&AuthenticatedUser>,
+    list_id: i32,
+) -> Result<entity::sauce_list::Model> {
+    let list = SauceList::find_by_id(list_id)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    if viewer.is_some_and(|viewer| viewer.user_id == list.user) {
+        return Ok(list);
+    }
+
+    if !list.public {
+        return Err(Error::NotFound);
+    }
+
+    let owner = User::find_by_id(list.user)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+    ensure_profile_visible(viewer, &owner)?;
+    ensure_not_ignored(conn, viewer, owner.id).await?;
+
+    Ok(list)
+}
+
+async fn find_own_list(
+    conn: &impl ConnectionTrait,
+    auth: &AuthenticatedUser,
+    list_id: i32,
+) -> Result<entity::sauce_list::Model> {
+    SauceList::find_by_id(list_id)
+        .filter(entity::sauce_list::Column::User.eq(auth.user_id))
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)
+}
+
+async fn list_entries(
+    conn: &impl ConnectionTrait,
+    list_id: i32,
+) -> Result<Vec<entity::sauce_list_entry::Model>> {
+    use entity::sauce_list_entry::Column;
+
+    Ok(SauceListEntry::find()
+        .filter(Column::List.eq(list_id))
+        .order_by_asc(Column::Position)
+        .order_by_asc(Column::Id)
+        .all(conn)
+        .await?)
+}
+
+#[derive(Debug, Serialize)]
+struct ListEntryResponse {
+    #[serde(flatten)]
+    entry: entity::sauce_list_entry::Model,
+    sauce_details: entity::sauce::Model,
+}
+
+#[derive(Debug, Serialize)]
+struct ListDetails {
+    #[serde(flatten)]
+    summary: ListSummary,
+    entries: Vec<ListEntryResponse>,
+}
+
+async fn list_details(
+    conn: &DatabaseConnection,
+    list: entity::sauce_list::Model,
+) -> Result<ListDetails> {
+    let entries = list_entries(conn, list.id).await?;
+
+    let sauces: HashMap<i32, entity::sauce::Model> = Sauce::find()
+        .filter(entity::sauce::Column::Id.is_in(entries.iter().map(|entry| entry.sauce)))
+        .filter(entity::sauce::Column::HiddenAt.is_null())
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|sauce| (sauce.id, sauce))
+        .collect();
+
+    let summary = list_summaries(conn, vec![list])
+        .await?
+        .pop()
+        .ok_or(Error::NotFound)?;
+
+    Ok(ListDetails {
+        summary,
+        // Entries for sauces hidden by a moderator are left out
+        entries: entries
+            .into_iter()
+            .filter_map(|entry| {
+                Some(ListEntryResponse {
+                    sauce_details: sauces.get(&entry.sauce)?.clone(),
+                    entry,
+                })
+            })
+            .collect(),
+    })
+}
+
+async fn get_list(
+    auth: Option<AuthenticatedUser>,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(list_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let list = find_visible_list(conn, auth.as_ref(), list_id).await?;
+    Ok(Json(list_details(conn, list).await?))
+}
+
+/// The user editable parts of a list
+#[derive(Debug, Deserialize)]
+struct ListParams {
+    name: String,
+    description: Option<String>,
+    public: Option<bool>,
+}
+
+fn validate_list_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() {
+        Err(Error::BadRequest("List name must not be empty".to_string()))
+    } else if name.chars().count() > MAX_LIST_NAME_LENGTH {
+        Err(Error::BadRequest(format!(
+            "List name must be at most {MAX_LIST_NAME_LENGTH} characters"
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// The list's name and description together, so both are screened
+fn list_text(params: &ListParams) -> String {
+    match &params.description {
+        Some(description) => format!("{}\n{description}", params.name),
+        None => params.name.clone(),
+    }
+}
+
+/// Lists don't go through the moderation queue, so text screening would hold is turned away instead
+fn reject_held(held: Option<ScreeningReason>) -> Result<()> {
+    match held {
+        Some(reason) => Err(Error::ContentRejected(reason)),
+        None => Ok(()),
+    }
+}
+
+async fn create_list(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(screening): Extension<Screening>,
+    Json(params): Json<ListParams>,
+) -> Result<impl IntoResponse> {
+    validate_list_name(&params.name)?;
+    reject_held(
+        screening
+            .check(conn, auth.user_id, Some(&list_text(&params)))
+            .await?,
+    )?;
+
+    let now = Utc::now();
+    let list = entity::sauce_list::ActiveModel {
+        user: Set(auth.user_id),
+        name: Set(params.name),
+        description: Set(params.description),
+        public: Set(params.public.unwrap_or(true)),
+        forked_from: Set(None),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await?;
+
+    record_list_activity(conn, &list).await?;
+
+    Ok(Json(list_details(conn, list).await?))
+}
+
+async fn update_list(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(screening): Extension<Screening>,
+    Path(list_id): Path<i32>,
+    Json(params): Json<ListParams>,
+) -> Result<impl IntoResponse> {
+    validate_list_name(&params.name)?;
+    reject_held(screening.check_edit(&list_text(&params))?)?;
+
+    let list = find_own_list(conn, &auth, list_id).await?;
+    let public = params.public.unwrap_or(list.public);
+
+    let mut list: entity::sauce_list::ActiveModel = list.into();
+    list.name = Set(params.name);
+    list.description = Set(params.description);
+    list.public = Set(public);
+    list.updated_at = Set(Utc::now());
+    let list = list.update(conn).await?;
+
+    record_list_activity(conn, &list).await?;
+
+    Ok(Json(list_details(conn, list).await?))
+}
+
+async fn delete_list(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(list_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let list = find_own_list(conn, &auth, list_id).await?;
+    SauceList::delete_by_id(list.id).exec(conn).await?;
+
+    Ok(Json(list))
+}
+
+/// Changes to private lists stay out of everyone's feeds
+async fn record_list_activity(
+    conn: &impl ConnectionTrait,
+    list: &entity::sauce_list::Model,
+) -> Result<()> {
+    if list.public {
+        record_activity(
+            conn,
+            Some(list.user),
+            NewActivity::ListUpdated { list: list.id },
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Bump the list's last updated time after a change to its entries
+async fn touch_list(conn: &impl ConnectionTrait, list: entity::sauce_list::Model) -> Result<()> {
+    let mut list: entity::sauce_list::ActiveModel = list.into();
+    list.updated_at = Set(Utc::now());
+    let list = list.update(conn).await?;
+
+    record_list_activity(conn, &list).await
+}
+
+#[derive(Debug, Deserialize)]
+struct NewListEntry {
+    sauce: i32,
+    commentary: Option<String>,
+}
+
+/// Add a sauce to the end of a list
+async fn add_entry(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(screening): Extension<Screening>,
+    Path(list_id): Path<i32>,
+    Json(new): Json<NewListEntry>,
+) -> Result<impl IntoResponse> {
+    let list = find_own_list(conn, &auth, list_id).await?;
+    if let Some(commentary) = &new.commentary {
+        reject_held(
+            screening
+                .check(conn, auth.user_id, Some(commentary))
+                .await?,
+        )?;
+    }
+
+    find_visible_sauce(conn, new.sauce).await?;
+
+    let entries = list_entries(conn, list.id).await?;
+    if entries.iter().any(|entry| entry.sauce == new.sauce) {
+        return Err(Error::BadRequest(
+            "That sauce is already on this list".to_string(),
+        ));
+    }
+
+    let position = entries.last().map_or(0, |entry| entry.position + 1);
+    let entry = entity::sauce_list_entry::ActiveModel {
+        list: Set(list.id),
+        sauce: Set(new.sauce),
+        position: Set(position),
+        commentary: Set(new.commentary),
+        ..Default::default()
+    }
+    .insert(conn)
+    .await?;
+
+    touch_list(conn, list).await?;
+
+    Ok(Json(entry))
+}
+
+async fn find_own_entry(
+    conn: &DatabaseConnection,
+    list: &entity::sauce_list::Model,
+    entry_id: i32,
+) -> Result<entity::sauce_list_entry::Model> {
+    SauceListEntry::find_by_id(entry_id)
+        .filter(entity::sauce_list_entry::Column::List.eq(list.id))
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)
+}
+
+#[derive(Debug, Deserialize)]
+struct EntryParams {
+    commentary: Option<String>,
+}
+
+async fn update_entry(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(screening): Extension<Screening>,
+    Path((list_id, entry_id)): Path<(i32, i32)>,
+    Json(params): Json<EntryParams>,
+) -> Result<impl IntoResponse> {
+    let list = find_own_list(conn, &auth, list_id).await?;
+    let entry = find_own_entry(conn, &list, entry_id).await?;
+    if let Some(commentary) = &params.commentary {
+        reject_held(screening.check_edit(commentary)?)?;
+    }
+
+    let mut entry: entity::sauce_list_entry::ActiveModel = entry.into();
+    entry.commentary = Set(params.commentary);
+    let entry = entry.update(conn).await?;
+
+    touch_list(conn, list).await?;
+
+    Ok(Json(entry))
+}
+
+async fn remove_entry(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path((list_id, entry_id)): Path<(i32, i32)>,
+) -> Result<impl IntoResponse> {
+    use entity::sauce_list_entry::Column;
+
+    let list = find_own_list(conn, &auth, list_id).await?;
+    let entry = find_own_entry(conn, &list, entry_id).await?;
+
+    let txn = conn.begin().await?;
+    SauceListEntry::delete_by_id(entry.id).exec(&txn).await?;
+
+    // Close the gap left behind so positions stay contiguous
+    SauceListEntry::update_many()
+        .col_expr(Column::Position, Expr::col(Column::Position).sub(1))
+        .filter(Column::List.eq(list.id))
+        .filter(Column::Position.gt(entry.position))
+        .exec(&txn)
+        .await?;
+
+    touch_list(&txn, list).await?;
+    txn.commit().await?;
+
+    Ok(Json(entry))
+}
+
+#[derive(Debug, Deserialize)]
+struct ReorderParams {
+    /// Every entry id in the list, in their new order
+    entries: Vec<i32>,
+}
+
+async fn reorder_entries(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(list_id): Path<i32>,
+    Json(params): Json<ReorderParams>,
+) -> Result<impl IntoResponse> {
+    use entity::sauce_list_entry::Column;
+
+    let list = find_own_list(conn, &auth, list_id).await?;
+
+    let txn = conn.begin().await?;
+    let existing: HashSet<i32> = list_entries(&txn, list.id)
+        .await?
+        .iter()
+        .map(|entry| entry.id)
+        .collect();
+    let requested: HashSet<i32> = params.entries.iter().copied().collect();
+
+    if requested.len() != params.entries.len() || requested != existing {
+        return Err(Error::BadRequest(
+            "The new order must contain every entry in the list exactly once".to_string(),
+        ));
+    }
+
+    for (position, entry_id) in params.entries.iter().enumerate() {
+        SauceListEntry::update_many()
+            .col_expr(Column::Position, Expr::value(position as i32))
+            .filter(Column::Id.eq(*entry_id))
+            .exec(&txn)
+            .await?;
+    }
+
+    touch_list(&txn, list.clone()).await?;
+    txn.commit().await?;
+
+    Ok(Json(list_details(conn, list).await?))
+}
+
+#[derive(Debug, Deserialize)]
+struct ForkParams {
+    /// Defaults to the name of the original list
+    name: Option<String>,
+}
+
+/// Copy someone else's list, entries and all, into the caller's account. Forks start out private.
+async fn fork_list(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(list_id): Path<i32>,
+    Json(params): Json<ForkParams>,
+) -> Result<impl IntoResponse> {
+    let original = find_visible_list(conn, Some(&auth), list_id).await?;
+
+    let name = params.name.unwrap_or_else(|| original.name.clone());
+    validate_list_name(&name)?;
+
+    let txn = conn.begin().await?;
+    let now = Utc::now();
+    let fork = entity::sauce_list::ActiveModel {
+        user: Set(auth.user_id),
+        name: Set(name),
+        description: Set(original.description.clone()),
+        public: Set(false),
+        forked_from: Set(Some(original.id)),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+
+    for entry in list_entries(&txn, original.id).await? {
+        entity::sauce_list_entry::ActiveModel {
+            list: Set(fork.id),
+            sauce: Set(entry.sauce),
+            position: Set(entry.position),
+            commentary: Set(entry.commentary),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+    }
+    txn.commit().await?;
+
+    Ok(Json(list_details(conn, fork).await?))
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/list", get(public_lists).post(create_list))
+        .route(
+            "/list/:list_id",
+            get(get_list).put(update_list).delete(delete_list),
+        )
+        .route("/list/:list_id/entry", post(add_entry))
+        .route(
+            "/list/:list_id/entry/:entry_id",
+            put(update_entry).delete(remove_entry),
+        )
+        .route("/list/:list_id/order", put(reorder_entries))
+        .route("/list/:list_id/fork", post(fork_list))
+        .route("/user/:username/lists", get(user_lists))
+}
@@ -0,0 +1,485 @@
+//! Checks run over user-written text before it's saved.
+//!
+//! Each [`Screen`] looks at a submission along with the author's recent submissions and decides
+//! whether to accept it, hold it back until a moderator has looked at it, or reject it outright.
+//! The strictest outcome of any screen wins.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use entity::prelude::*;
+use sea_orm::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ScreeningConfig {
+    /// Words or phrases that get a submission rejected
+    pub blocked_words: Vec<String>,
+
+    /// Words or phrases that get a submission held for moderation
+    pub flagged_words: Vec<String>,
+
+    /// Submissions with more links than this are held for moderation
+    pub max_links: usize,
+
+    /// How long an identical submission from the same user is rejected for, in seconds
+    pub duplicate_window_secs: i64,
+
+    /// How many submissions a user can make within `rate_window_secs`
+    pub max_submissions: usize,
+    pub rate_window_secs: i64,
+}
+
+impl Default for ScreeningConfig {
+    fn default() -> Self {
+        ScreeningConfig {
+            blocked_words: Vec::new(),
+            flagged_words: Vec::new(),
+            max_links: 2,
+            duplicate_window_secs: 24 * 60 * 60,
+            max_submissions: 20,
+            rate_window_secs: 60 * 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreeningCode {
+    BlockedWord,
+    FlaggedWord,
+    TooManyLinks,
+    Duplicate,
+    TooManySubmissions,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScreeningReason {
+    pub code: ScreeningCode,
+    pub message: String,
+}
+
+impl ScreeningReason {
+    fn new(code: ScreeningCode, message: impl Into<String>) -> Self {
+        ScreeningReason {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Verdict {
+    Accept,
+    Hold(ScreeningReason),
+    Reject(ScreeningReason),
+}
+
+/// Text a user is about to save
+#[derive(Debug)]
+pub struct Submission<'a> {
+    pub text: Option<&'a str>,
+    pub submitted_at: DateTime<Utc>,
+}
+
+/// A user's earlier review or comment
+#[derive(Debug)]
+pub struct PastSubmission {
+    pub text: Option<String>,
+    pub submitted_at: DateTime<Utc>,
+}
+
+pub trait Screen: Send + Sync {
+    /// How far back this screen needs to see the author's earlier submissions
+    fn history_window(&self) -> Duration {
+        Duration::zero()
+    }
+
+    fn screen(&self, submission: &Submission, history: &[PastSubmission]) -> Verdict;
+}
+
+/// Lowercase words separated by single spaces, with a space either end so whole words and
+/// phrases can be found with a plain substring search
+fn normalise(text: &str) -> String {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+
+    format!(" {} ", words.join(" "))
+}
+
+struct WordList {
+    words: Vec<String>,
+    code: ScreeningCode,
+    reject: bool,
+}
+
+impl WordList {
+    fn new(words: &[String], code: ScreeningCode, reject: bool) -> Self {
+        WordList {
+            words: words
+                .iter()
+                .map(|word| normalise(word))
+                .filter(|word| !word.trim().is_empty())
+                .collect(),
+            code,
+            reject,
+        }
+    }
+}
+
+impl Screen for WordList {
+    fn screen(&self, submission: &Submission, _history: &[PastSubmission]) -> Verdict {
+        let text = match submission.text {
+            Some(text) => normalise(text),
+            None => return Verdict::Accept,
+        };
+
+        match self.words.iter().find(|word| text.contains(word.as_str())) {
+            Some(word) => {
+                let reason =
+                    ScreeningReason::new(self.code, format!("Contains \"{}\"", word.trim()));
+                match self.reject {
+                    true => Verdict::Reject(reason),
+                    false => Verdict::Hold(reason),
+                }
+            }
+            None => Verdict::Accept,
+        }
+    }
+}
+
+struct LinkLimit {
+    max_links: usize,
+}
+
+impl Screen for LinkLimit {
+    fn screen(&self, submission: &Submission, _history: &[PastSubmission]) -> Verdict {
+        let links = submission
+            .text
+            .unwrap_or_default()
+            .split_whitespace()
+            .filter(|word| {
+                let word = word.to_lowercase();
+                word.contains("http://") || word.contains("https://") || word.contains("www.")
+            })
+            .count();
+
+        match links > self.max_links {
+            true => Verdict::Hold(ScreeningReason::new(
+                ScreeningCode::TooManyLinks,
+                format!("Contains more than {} links", self.max_links),
+            )),
+            false => Verdict::Accept,
+        }
+    }
+}
+
+/// Rejects the same text being posted again, which catches both spam and double submits
+struct RepeatSubmission {
+    window: Duration,
+}
+
+impl Screen for RepeatSubmission {
+    fn history_window(&self) -> Duration {
+        self.window
+    }
+
+    fn screen(&self, submission: &Submission, history: &[PastSubmission]) -> Verdict {
+        let text = match submission.text.map(normalise) {
+            Some(text) if !text.trim().is_empty() => text,
+            _ => return Verdict::Accept,
+        };
+
+        let since = submission.submitted_at - self.window;
+        let repeated = history
+            .iter()
+            .filter(|past| past.submitted_at >= since)
+            .filter_map(|past| past.text.as_deref())
+            .any(|past| normalise(past) == text);
+
+        match repeated {
+            true => Verdict::Reject(ScreeningReason::new(
+                ScreeningCode::Duplicate,
+                "You've already posted this",
+            )),
+            false => Verdict::Accept,
+        }
+    }
+}
+
+struct SubmissionRate {
+    max_submissions: usize,
+    window: Duration,
+}
+
+impl Screen for SubmissionRate {
+    fn history_window(&self) -> Duration {
+        self.window
+    }
+
+    fn screen(&self, submission: &Submission, history: &[PastSubmission]) -> Verdict {
+        let since = submission.submitted_at - self.window;
+        let recent = history
+            .iter()
+            .filter(|past| past.submitted_at >= since)
+            .count();
+
+        match recent >= self.max_submissions {
+            true => Verdict::Reject(ScreeningReason::new(
+                ScreeningCode::TooManySubmissions,
+                "You're posting too quickly, try again later",
+            )),
+            false => Verdict::Accept,
+        }
+    }
+}
+
+/// The screens every submission passes through
+#[derive(Clone)]
+pub struct Screening {
+    screens: Arc<Vec<Box<dyn Screen>>>,
+}
+
+impl Screening {
+    pub fn new(screens: Vec<Box<dyn Screen>>) -> Self {
+        Screening {
+            screens: Arc::new(screens),
+        }
+    }
+
+    pub fn from_config(config: &ScreeningConfig) -> Self {
+        Screening::new(vec![
+            Box::new(WordList::new(
+                &config.blocked_words,
+                ScreeningCode::BlockedWord,
+                true,
+            )),
+            Box::new(WordList::new(
+                &config.flagged_words,
+                ScreeningCode::FlaggedWord,
+                false,
+            )),
+            Box::new(LinkLimit {
+                max_links: config.max_links,
+            }),
+            Box::new(RepeatSubmission {
+                window: Duration::seconds(config.duplicate_window_secs),
+            }),
+            Box::new(SubmissionRate {
+                max_submissions: config.max_submissions,
+                window: Duration::seconds(config.rate_window_secs),
+            }),
+        ])
+    }
+
+    /// The user's reviews and comments from as far back as any screen needs
+    async fn history(
+        &self,
+        conn: &DatabaseConnection,
+        user_id: i32,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<PastSubmission>> {
+        let window = self
+            .screens
+            .iter()
+            .map(|screen| screen.history_window())
+            .max()
+            .unwrap_or_else(Duration::zero);
+        if window <= Duration::zero() {
+            return Ok(Vec::new());
+        }
+        let since = now - window;
+
+        let reviews = Review::find()
+            .filter(entity::review::Column::User.eq(user_id))
+            .filter(entity::review::Column::Timestamp.gte(since))
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|review| PastSubmission {
+                text: review.text,
+                submitted_at: review.timestamp,
+            });
+
+        let comments = ReviewComment::find()
+            .filter(entity::review_comment::Column::Author.eq(user_id))
+            .filter(entity::review_comment::Column::CreatedAt.gte(since))
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|comment| PastSubmission {
+                text: Some(comment.body),
+                submitted_at: comment.created_at,
+            });
+
+        Ok(reviews.chain(comments).collect())
+    }
+
+    fn run(
+        &self,
+        submission: &Submission,
+        history: &[PastSubmission],
+    ) -> Result<Option<ScreeningReason>> {
+        let mut hold = None;
+        for screen in self.screens.iter() {
+            match screen.screen(submission, history) {
+                Verdict::Accept => {}
+                Verdict::Hold(reason) => {
+                    hold.get_or_insert(reason);
+                }
+                Verdict::Reject(reason) => return Err(Error::ContentRejected(reason)),
+            }
+        }
+
+        Ok(hold)
+    }
+
+    /// Run new text from a user through every screen. Rejections come back as an error, while a
+    /// reason is returned if the text should be saved but held for moderation.
+    pub async fn check(
+        &self,
+        conn: &DatabaseConnection,
+        user_id: i32,
+        text: Option<&str>,
+    ) -> Result<Option<ScreeningReason>> {
+        let submission = Submission {
+            text,
+            submitted_at: Utc::now(),
+        };
+        let history = self.history(conn, user_id, submission.submitted_at).await?;
+
+        self.run(&submission, &history)
+    }
+
+    /// Like [`Screening::check`], for edits to existing text. Edits aren't new submissions, so
+    /// they're only checked for what they say.
+    pub fn check_edit(&self, text: &str) -> Result<Option<ScreeningReason>> {
+        let submission = Submission {
+            text: Some(text),
+            submitted_at: Utc::now(),
+        };
+
+        self.run(&submission, &[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screening() -> Screening {
+        Screening::from_config(&ScreeningConfig {
+            blocked_words: vec!["buy now".to_string(), "Scam".to_string()],
+            flagged_words: vec!["idiot".to_string()],
+            max_links: 1,
+            duplicate_window_secs: 60,
+            max_submissions: 3,
+            rate_window_secs: 60,
+        })
+    }
+
+    fn code(result: Result<Option<ScreeningReason>>) -> Option<&'static str> {
+        match result {
+            Ok(None) => None,
+            Ok(Some(reason)) => Some(match reason.code {
+                ScreeningCode::FlaggedWord => "held: flagged word",
+                ScreeningCode::TooManyLinks => "held: too many links",
+                _ => "held: other",
+            }),
+            Err(Error::ContentRejected(reason)) => Some(match reason.code {
+                ScreeningCode::BlockedWord => "rejected: blocked word",
+                ScreeningCode::Duplicate => "rejected: duplicate",
+                ScreeningCode::TooManySubmissions => "rejected: too many submissions",
+                _ => "rejected: other",
+            }),
+            Err(e) => panic!("unexpected error {e:?}"),
+        }
+    }
+
+    fn past(text: &str, seconds_ago: i64) -> PastSubmission {
+        PastSubmission {
+            text: Some(text.to_string()),
+            submitted_at: Utc::now() - Duration::seconds(seconds_ago),
+        }
+    }
+
+    fn submit(text: &str, history: &[PastSubmission]) -> Option<&'static str> {
+        let submission = Submission {
+            text: Some(text),
+            submitted_at: Utc::now(),
+        };
+        code(screening().run(&submission, history))
+    }
+
+    #[test]
+    fn accepts_ordinary_text() {
+        assert_eq!(
+            code(screening().check_edit("Fruity, with a slow burn")),
+            None
+        );
+    }
+
+    #[test]
+    fn word_lists_match_whole_words_and_phrases() {
+        let screening = screening();
+        assert_eq!(
+            code(screening.check_edit("What a SCAM!")),
+            Some("rejected: blocked word")
+        );
+        assert_eq!(
+            code(screening.check_edit("Buy... now, while it lasts")),
+            Some("rejected: blocked word")
+        );
+        assert_eq!(
+            code(screening.check_edit("Only an idiot would skip this")),
+            Some("held: flagged word")
+        );
+        // Words inside other words don't count
+        assert_eq!(code(screening.check_edit("Scampi and idiotic")), None);
+        assert_eq!(code(screening.check_edit("buy it now")), None);
+    }
+
+    #[test]
+    fn rejection_beats_hold() {
+        assert_eq!(
+            code(screening().check_edit("idiot scam")),
+            Some("rejected: blocked word")
+        );
+    }
+
+    #[test]
+    fn links_over_the_limit_are_held() {
+        let screening = screening();
+        assert_eq!(code(screening.check_edit("See https://example.com")), None);
+        assert_eq!(
+            code(screening.check_edit("See https://example.com and www.example.org")),
+            Some("held: too many links")
+        );
+    }
+
+    #[test]
+    fn repeats_within_the_window_are_rejected() {
+        assert_eq!(
+            submit("Great sauce!", &[past("great  SAUCE", 30)]),
+            Some("rejected: duplicate")
+        );
+        assert_eq!(submit("Great sauce!", &[past("great sauce", 120)]), None);
+    }
+
+    #[test]
+    fn submission_rate_is_limited() {
+        let history = [past("one", 10), past("two", 20), past("three", 30)];
+        assert_eq!(
+            submit("four", &history),
+            Some("rejected: too many submissions")
+        );
+        let history = [past("one", 10), past("two", 20), past("three", 90)];
+        assert_eq!(submit("four", &history), None);
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query},
+    response::IntoResponse,
+    routing::{get, put},
+    Extension, Json, Router,
+};
+use chrono::{NaiveDate, Utc};
+use entity::{prelude::*, sea_orm_active_enums::ShelfStatus};
+use sea_orm::{prelude::*, QueryOrder, Set};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::AuthenticatedUser,
+    error::{Error, Result},
+};
+
+#[derive(Debug, Serialize)]
+struct ShelfEntryResponse {
+    #[serde(flatten)]
+    entry: entity::shelf_entry::Model,
+    sauce_details: Option<entity::sauce::Model>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ShelfQuery {
+    status: Option<ShelfStatus>,
+}
+
+async fn shelf_list(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Query(query): Query<ShelfQuery>,
+) -> Result<impl IntoResponse> {
+    use entity::shelf_entry::Column;
+
+    let mut find = ShelfEntry::find()
+        .filter(Column::User.eq(auth.user_id))
+        .order_by_desc(Column::UpdatedAt);
+    if let Some(status) = query.status {
+        find = find.filter(Column::Status.eq(status));
+    }
+
+    let entries = find.all(conn).await?;
+
+    let sauces: HashMap<i32, entity::sauce::Model> = Sauce::find()
+        .filter(entity::sauce::Column::Id.is_in(entries.iter().map(|entry| entry.sauce)))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|sauce| (sauce.id, sauce))
+        .collect();
+
+    let response: Vec<_> = entries
+        .into_iter()
+        .map(|entry| ShelfEntryResponse {
+            sauce_details: sauces.get(&entry.sauce).cloned(),
+            entry,
+        })
+        .collect();
+
+    Ok(Json(response))
+}
+
+/// The user editable parts of a shelf entry
+#[derive(Debug, Deserialize)]
+struct ShelfEntryParams {
+    status: ShelfStatus,
+    quantity: Option<i32>,
+    acquired_on: Option<NaiveDate>,
+    notes: Option<String>,
+}
+
+impl ShelfEntryParams {
+    fn validate(&self) -> Result<()> {
+        if self.quantity.is_some_and(|quantity| quantity < 0) {
+            return Err(Error::BadRequest(
+                "Quantity must not be negative".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn apply(self, entry: &mut entity::shelf_entry::ActiveModel) {
+        entry.status = Set(self.status);
+        entry.quantity = Set(self.quantity.unwrap_or(1));
+        entry.acquired_on = Set(self.acquired_on);
+        entry.notes = Set(self.notes);
+        entry.updated_at = Set(Utc::now());
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct NewShelfEntry {
+    sauce: i32,
+    #[serde(flatten)]
+    params: ShelfEntryParams,
+}
+
+/// Fail if the user already has the sauce on their shelf with the same status, other than in the
+/// entry being edited
+async fn ensure_not_duplicate(
+    conn: &DatabaseConnection,
+    user_id: i32,
+    sauce_id: i32,
+    status: ShelfStatus,
+    editing: Option<i32>,
+) -> Result<()> {
+    use entity::shelf_entry::Column;
+
+    let mut find = ShelfEntry::find()
+        .filter(Column::User.eq(user_id))
+        .filter(Column::Sauce.eq(sauce_id))
+        .filter(Column::Status.eq(status));
+    if let Some(id) = editing {
+        find = find.filter(Column::Id.ne(id));
+    }
+
+    if find.one(conn).await?.is_some() {
+        Err(Error::BadRequest(
+            "That sauce is already on your shelf with the same status".to_string(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+async fn shelf_insert(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Json(new): Json<NewShelfEntry>,
+) -> Result<impl IntoResponse> {
+    new.params.validate()?;
+
+    Sauce::find_by_id(new.sauce)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    ensure_not_duplicate(conn, auth.user_id, new.sauce, new.params.status, None).await?;
+
+    let now = Utc::now();
+    let mut entry = entity::shelf_entry::ActiveModel {
+        user: Set(auth.user_id),
+        sauce: Set(new.sauce),
+        created_at: Set(now),
+        ..Default::default()
+    };
+    new.params.apply(&mut entry);
+
+    Ok(Json(entry.insert(conn).await?))
+}
+
+async fn find_own_entry(
+    conn: &DatabaseConnection,
+    auth: &AuthenticatedUser,
+    entry_id: i32,
+) -> Result<entity::shelf_entry::Model> {
+    ShelfEntry::find_by_id(entry_id)
+        .filter(entity::shelf_entry::Column::User.eq(auth.user_id))
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)
+}
+
+async fn shelf_update(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(entry_id): Path<i32>,
+    Json(params): Json<ShelfEntryParams>,
+) -> Result<impl IntoResponse> {
+    params.validate()?;
+
+    let entry = find_own_entry(conn, &auth, entry_id).await?;
+    ensure_not_duplicate(
+        conn,
+        auth.user_id,
+        entry.sauce,
+        params.status,
+        Some(entry.id),
+    )
+    .await?;
+
+    let mut entry: entity::shelf_entry::ActiveModel = entry.into();
+    params.apply(&mut entry);
+
+    Ok(Json(entry.update(conn).await?))
+}
+
+async fn shelf_delete(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(entry_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let entry = find_own_entry(conn, &auth, entry_id).await?;
+    ShelfEntry::delete_by_id(entry.id).exec(conn).await?;
+
+    Ok(Json(entry))
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/shelf", get(shelf_list).post(shelf_insert))
+        .route("/shelf/:entry_id", put(shelf_update).delete(shelf_delete))
+}
@@ -0,0 +1,93 @@
+//! Where uploaded files end up.
+//!
+//! Files are addressed by `/`-separated keys chosen by the api, so a backend only has to store
+//! and hand back bytes. Only local storage exists for now, but anything with an object-store shape
+//! (S3 and friends) fits behind the same trait.
+
+use std::{
+    io::ErrorKind,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<()>;
+
+    /// `None` if nothing is stored under the key
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Deleting a key that doesn't exist isn't an error
+    async fn delete(&self, key: &str) -> Result<()>;
+}
+
+pub type SharedStorage = Arc<dyn Storage>;
+
+/// Stores files in a directory on the local filesystem
+#[derive(Debug)]
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        std::fs::create_dir_all(&root)?;
+
+        Ok(LocalStorage { root })
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf> {
+        // Keys are never built from user input, but make sure one can't escape the root anyway
+        let relative = Path::new(key);
+        if !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(anyhow!("Invalid storage key {key:?}"));
+        }
+
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<()> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        tokio::fs::write(path, data).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match tokio::fs::read(self.path(key)?).await {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Remove files from storage. Failures are only logged, since whatever referred to the files is
+/// already gone and an orphaned file does no harm.
+pub async fn delete_stored_files(storage: &SharedStorage, keys: &[String]) {
+    for key in keys {
+        if let Err(e) = storage.delete(key).await {
+            log::error!("Failed to delete stored file {key:?}: {e:?}");
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::{
+    extract::Path,
+    response::IntoResponse,
+    routing::{get, put},
+    Extension, Json, Router,
+};
+use entity::prelude::*;
+use sea_orm::{prelude::*, FromQueryResult, QuerySelect, Set};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::AuthenticatedUser,
+    block::ensure_not_ignored,
+    error::{Error, Result},
+    profile::{ensure_profile_visible, find_user_by_username},
+    ratings::{
+        axis_averages, axis_ratings, mean, AxisAverage, HeatBand, HEAT_AXIS_NAME, OVERALL_AXIS_NAME,
+    },
+};
+
+/// How many manufacturers/peppers are listed as a user's favourites
+const FAVOURITES_COUNT: usize = 3;
+
+#[derive(Debug, Serialize)]
+struct HeatBandSummary {
+    band: HeatBand,
+    sauces: usize,
+    average_overall: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+struct Favourite {
+    id: i32,
+    name: String,
+    sauces: usize,
+    average_overall: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct RatingBucket {
+    rating: i32,
+    count: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct TasteProfile {
+    user_id: i32,
+    username: String,
+    review_count: usize,
+    sauces_tried: usize,
+
+    /// The average rating the user has given on each axis
+    axis_averages: Vec<AxisAverage>,
+
+    /// The heat band of the sauces the user has given the best Overall ratings to, where the heat
+    /// of each sauce is judged by the community rather than just this user
+    preferred_heat_band: Option<HeatBand>,
+    heat_bands: Vec<HeatBandSummary>,
+
+    favourite_manufacturers: Vec<Favourite>,
+    favourite_peppers: Vec<Favourite>,
+
+    /// Number of Overall ratings given, bucketed to the nearest whole point
+    rating_distribution: Vec<RatingBucket>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct SauceAxisRating {
+    sauce: i32,
+    rating_axis: i32,
+    rating: f64,
+}
+
+/// Rank groups of sauces (eg. all sauces from one manufacturer) by the user's mean Overall score
+fn favourites(
+    sauces_by_group: HashMap<i32, Vec<i32>>,
+    group_names: HashMap<i32, String>,
+    overall_by_sauce: &HashMap<i32, f64>,
+) -> Vec<Favourite> {
+    let mut favourites: Vec<_> = sauces_by_group
+        .into_iter()
+        .filter_map(|(id, sauces)| {
+            let scores: Vec<f64> = sauces
+                .iter()
+                .filter_map(|sauce| overall_by_sauce.get(sauce).copied())
+                .collect();
+
+            Some(Favourite {
+                id,
+                name: group_names.get(&id)?.clone(),
+                sauces: sauces.len(),
+                average_overall: mean(&scores)?,
+            })
+        })
+        .collect();
+
+    favourites.sort_by(|a, b| {
+        b.average_overall
+            .total_cmp(&a.average_overall)
+            .then(b.sauces.cmp(&a.sauces))
+    });
+    favourites.truncate(FAVOURITES_COUNT);
+    favourites
+}
+
+async fn build_taste_profile(
+    conn: &DatabaseConnection,
+    user: entity::user::Model,
+) -> Result<TasteProfile> {
+    // Hidden reviews are left out, so moderated or held content doesn't show up in the profile
+    let reviews = Review::find()
+        .filter(entity::review::Column::User.eq(user.id))
+        .filter(entity::review::Column::HiddenAt.is_null())
+        .all(conn)
+        .await?;
+    let sauces_tried: HashSet<i32> = reviews.iter().map(|review| review.sauce).collect();
+
+    let ratings = ReviewRating::find()
+        .select_only()
+        .column_as(entity::review::Column::Sauce, "sauce")
+        .column(entity::review_rating::Column::RatingAxis)
+        .column(entity::review_rating::Column::Rating)
+        .inner_join(Review)
+        .filter(entity::review::Column::User.eq(user.id))
+        .filter(entity::review::Column::HiddenAt.is_null())
+        .into_model::<SauceAxisRating>()
+        .all(conn)
+        .await?;
+
+    let axes = RatingAxis::find().all(conn).await?;
+
+    let mut by_axis: HashMap<i32, Vec<f64>> = HashMap::new();
+    for r in &ratings {
+        by_axis.entry(r.rating_axis).or_default().push(r.rating);
+    }
+
+    let axis_averages = axis_averages(&axes, &by_axis);
+
+    let overall_axis = axes.iter().find(|axis| axis.name == OVERALL_AXIS_NAME);
+    let heat_axis = axes.iter().find(|axis| axis.name == HEAT_AXIS_NAME);
+
+    let mut overall_by_sauce = HashMap::new();
+    let mut rating_distribution = Vec::new();
+    if let Some(axis) = overall_axis {
+        let mut per_sauce: HashMap<i32, Vec<f64>> = HashMap::new();
+        let mut buckets: HashMap<i32, usize> = HashMap::new();
+        for r in ratings.iter().filter(|r| r.rating_axis == axis.id) {
+            per_sauce.entry(r.sauce).or_default().push(r.rating);
+            *buckets.entry(r.rating.round() as i32).or_default() += 1;
+        }
+
+        overall_by_sauce = per_sauce
+            .into_iter()
+            .filter_map(|(sauce, values)| Some((sauce, mean(&values)?)))
+            .collect();
+
+        rating_distribution = (axis.min_value.round() as i32..=axis.max_value.round() as i32)
+            .map(|rating| RatingBucket {
+                rating,
+                count: buckets.get(&rating).copied().unwrap_or(0),
+            })
+            .collect();
+    }
+
+    let mut heat_bands = Vec::new();
+    if let Some(axis) = heat_axis {
+        let mut community_heat: HashMap<i32, Vec<f64>> = HashMap::new();
+        for r in axis_ratings(conn, axis.id, None).await? {
+            if sauces_tried.contains(&r.sauce) {
+                community_heat.entry(r.sauce).or_default().push(r.rating);
+            }
+        }
+
+        let axis_range = axis.max_value - axis.min_value;
+        let mut sauces_by_band: HashMap<HeatBand, Vec<i32>> = HashMap::new();
+        for (sauce, values) in community_heat {
+            if let Some(heat) = mean(&values) {
+                let band = HeatBand::from_fraction((heat - axis.min_value) / axis_range);
+                sauces_by_band.entry(band).or_default().push(sauce);
+            }
+        }
+
+        heat_bands = sauces_by_band
+            .into_iter()
+            .map(|(band, sauces)| {
+                let scores: Vec<f64> = sauces
+                    .iter()
+                    .filter_map(|sauce| overall_by_sauce.get(sauce).copied())
+                    .collect();
+
+                HeatBandSummary {
+                    band,
+                    sauces: sauces.len(),
+                    average_overall: mean(&scores),
+                }
+            })
+            .collect();
+        heat_bands.sort_by_key(|summary| summary.band);
+    }
+
+    let preferred_heat_band = heat_bands
+        .iter()
+        .filter_map(|summary| Some((summary, summary.average_overall?)))
+        .max_by(|(a, a_score), (b, b_score)| {
+            a_score.total_cmp(b_score).then(a.sauces.cmp(&b.sauces))
+        })
+        .map(|(summary, _)| summary.band);
+
+    let tried_ids: Vec<i32> = sauces_tried.iter().copied().collect();
+
+    let sauces = Sauce::find()
+        .filter(entity::sauce::Column::Id.is_in(tried_ids.clone()))
+        .all(conn)
+        .await?;
+    let mut sauces_by_manufacturer: HashMap<i32, Vec<i32>> = HashMap::new();
+    for sauce in &sauces {
+        sauces_by_manufacturer
+            .entry(sauce.manufacturer)
+            .or_default()
+            .push(sauce.id);
+    }
+    let manufacturer_names = Manufacturer::find()
+        .filter(entity::manufacturer::Column::Id.is_in(sauces_by_manufacturer.keys().copied()))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|m| (m.id, m.name))
+        .collect();
+
+    let mut sauces_by_pepper: HashMap<i32, Vec<i32>> = HashMap::new();
+    for sauce_pepper in SaucePepper::find()
+        .filter(entity::sauce_pepper::Column::Sauce.is_in(tried_ids))
+        .all(conn)
+        .await?
+    {
+        sauces_by_pepper
+            .entry(sauce_pepper.pepper)
+            .or_default()
+            .push(sauce_pepper.sauce);
+    }
+    let pepper_names = Pepper::find()
+        .filter(entity::pepper::Column::Id.is_in(sauces_by_pepper.keys().copied()))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|p| (p.id, p.name))
+        .collect();
+
+    Ok(TasteProfile {
+        user_id: user.id,
+        username: user.username,
+        review_count: reviews.len(),
+        sauces_tried: sauces_tried.len(),
+        axis_averages,
+        preferred_heat_band,
+        heat_bands,
+        favourite_manufacturers: favourites(
+            sauces_by_manufacturer,
+            manufacturer_names,
+            &overall_by_sauce,
+        ),
+        favourite_peppers: favourites(sauces_by_pepper, pepper_names, &overall_by_sauce),
+        rating_distribution,
+    })
+}
+
+async fn own_taste_profile(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+) -> Result<impl IntoResponse> {
+    let user = User::find_by_id(auth.user_id)
+        .one(conn)
+        .await?
+        .ok_or(Error::Unauthorized)?;
+
+    Ok(Json(build_taste_profile(conn, user).await?))
+}
+
+async fn user_taste_profile(
+    auth: Option<AuthenticatedUser>,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(username): Path<String>,
+) -> Result<impl IntoResponse> {
+    let user = find_user_by_username(conn, &username).await?;
+    ensure_profile_visible(auth.as_ref(), &user)?;
+    ensure_not_ignored(conn, auth.as_ref(), user.id).await?;
+
+    let is_self = auth.is_some_and(|auth| auth.user_id == user.id);
+    if !is_self && !user.taste_profile_public {
+        return Err(Error::Forbidden);
+    }
+
+    Ok(Json(build_taste_profile(conn, user).await?))
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct TasteProfileVisibility {
+    public: bool,
+}
+
+async fn set_taste_profile_visibility(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Json(visibility): Json<TasteProfileVisibility>,
+) -> Result<impl IntoResponse> {
+    let user = User::find_by_id(auth.user_id)
+        .one(conn)
+        .await?
+        .ok_or(Error::Unauthorized)?;
+
+    let mut user: entity::user::ActiveModel = user.into();
+    user.taste_profile_public = Set(visibility.public);
+    let user = user.update(conn).await?;
+
+    Ok(Json(TasteProfileVisibility {
+        public: user.taste_profile_public,
+    }))
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/taste_profile", get(own_taste_profile))
+        .route(
+            "/taste_profile/visibility",
+            put(set_taste_profile_visibility),
+        )
+        .route("/user/:username/taste_profile", get(user_taste_profile))
+}
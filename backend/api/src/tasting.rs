@@ -0,0 +1,713 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::Path,
+    response::IntoResponse,
+    routing::{get, post, put},
+    Extension, Json, Router,
+};
+use chrono::Utc;
+use entity::{prelude::*, sea_orm_active_enums::ModerationTarget};
+use rand::{seq::SliceRandom, Rng};
+use sea_orm::{prelude::*, ConnectionTrait, QueryOrder, Set, TransactionTrait};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::AuthenticatedUser,
+    error::{Error, Result},
+    moderation::hold_for_moderation,
+    ratings::{axis_averages, validate_ratings, AxisAverage, AxisRating, OVERALL_AXIS_NAME},
+    review::insert_review,
+    screening::Screening,
+    tasting_events::{TastingEvent, TastingEvents},
+};
+
+/// Sauces are labelled with a single letter, so this many at most can go into one session
+const MAX_SESSION_SAUCES: usize = 26;
+
+const JOIN_CODE_LENGTH: usize = 6;
+
+/// Characters used in join codes, leaving out ones that are easy to mix up when read aloud or
+/// copied off a whiteboard
+const JOIN_CODE_CHARSET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+fn generate_join_code() -> String {
+    let mut rng = rand::thread_rng();
+    (0..JOIN_CODE_LENGTH)
+        .map(|_| JOIN_CODE_CHARSET[rng.gen_range(0..JOIN_CODE_CHARSET.len())] as char)
+        .collect()
+}
+
+/// Generate a join code that isn't already used by another session
+async fn unused_join_code(conn: &impl ConnectionTrait) -> Result<String> {
+    loop {
+        let code = generate_join_code();
+        let existing = TastingSession::find()
+            .filter(entity::tasting_session::Column::JoinCode.eq(code.clone()))
+            .one(conn)
+            .await?;
+
+        if existing.is_none() {
+            return Ok(code);
+        }
+    }
+}
+
+fn sauce_label(index: usize) -> String {
+    format!("Sauce {}", (b'A' + index as u8) as char)
+}
+
+#[derive(Debug, Serialize)]
+struct TastingSauceResponse {
+    id: i32,
+    label: String,
+
+    /// Which sauce is behind the label, only filled in once the session has been revealed
+    sauce_details: Option<entity::sauce::Model>,
+}
+
+#[derive(Debug, Serialize)]
+struct ScorecardResponse {
+    #[serde(flatten)]
+    scorecard: entity::tasting_scorecard::Model,
+    ratings: Vec<AxisRating>,
+}
+
+/// Who has handed in a scorecard for which sauce, without giving away what they scored
+#[derive(Debug, Serialize)]
+struct Submission {
+    user: i32,
+    username: String,
+    tasting_sauce: i32,
+}
+
+#[derive(Debug, Serialize)]
+struct SauceResult {
+    tasting_sauce: i32,
+    label: String,
+    sauce_details: Option<entity::sauce::Model>,
+    scorecard_count: usize,
+    axis_averages: Vec<AxisAverage>,
+}
+
+#[derive(Debug, Serialize)]
+struct SessionResponse {
+    #[serde(flatten)]
+    session: entity::tasting_session::Model,
+    host_username: String,
+    is_host: bool,
+    participant_count: usize,
+    sauces: Vec<TastingSauceResponse>,
+    submissions: Vec<Submission>,
+
+    /// The caller's own scorecards
+    scorecards: Vec<ScorecardResponse>,
+
+    /// Per-sauce averages across every participant, ordered by Overall score. Only available once
+    /// the session has been revealed.
+    results: Option<Vec<SauceResult>>,
+}
+
+async fn session_sauces(
+    conn: &impl ConnectionTrait,
+    session_id: i32,
+) -> Result<Vec<entity::tasting_sauce::Model>> {
+    use entity::tasting_sauce::Column;
+
+    Ok(TastingSauce::find()
+        .filter(Column::Session.eq(session_id))
+        .order_by_asc(Column::Label)
+        .all(conn)
+        .await?)
+}
+
+/// Scorecards for the given sauces along with their ratings, optionally restricted to one user
+async fn scorecards(
+    conn: &impl ConnectionTrait,
+    tasting_sauces: &[entity::tasting_sauce::Model],
+    user_id: Option<i32>,
+) -> Result<Vec<ScorecardResponse>> {
+    let mut find = TastingScorecard::find().filter(
+        entity::tasting_scorecard::Column::TastingSauce
+            .is_in(tasting_sauces.iter().map(|sauce| sauce.id)),
+    );
+    if let Some(user_id) = user_id {
+        find = find.filter(entity::tasting_scorecard::Column::User.eq(user_id));
+    }
+    let scorecards = find.all(conn).await?;
+
+    let mut ratings: HashMap<i32, Vec<AxisRating>> = HashMap::new();
+    for rating in TastingScorecardRating::find()
+        .filter(
+            entity::tasting_scorecard_rating::Column::Scorecard
+                .is_in(scorecards.iter().map(|scorecard| scorecard.id)),
+        )
+        .all(conn)
+        .await?
+    {
+        ratings
+            .entry(rating.scorecard)
+            .or_default()
+            .push(AxisRating {
+                rating_axis: rating.rating_axis,
+                rating: rating.rating,
+            });
+    }
+
+    Ok(scorecards
+        .into_iter()
+        .map(|scorecard| ScorecardResponse {
+            ratings: ratings.remove(&scorecard.id).unwrap_or_default(),
+            scorecard,
+        })
+        .collect())
+}
+
+fn session_results(
+    tasting_sauces: &[entity::tasting_sauce::Model],
+    sauce_details: &HashMap<i32, entity::sauce::Model>,
+    scorecards: &[ScorecardResponse],
+    axes: &[entity::rating_axis::Model],
+) -> Vec<SauceResult> {
+    let mut results: Vec<SauceResult> = tasting_sauces
+        .iter()
+        .map(|tasting_sauce| {
+            let sauce_scorecards: Vec<&ScorecardResponse> = scorecards
+                .iter()
+                .filter(|s| s.scorecard.tasting_sauce == tasting_sauce.id)
+                .collect();
+
+            let mut by_axis: HashMap<i32, Vec<f64>> = HashMap::new();
+            for rating in sauce_scorecards.iter().flat_map(|s| &s.ratings) {
+                by_axis
+                    .entry(rating.rating_axis)
+                    .or_default()
+                    .push(rating.rating);
+            }
+
+            SauceResult {
+                tasting_sauce: tasting_sauce.id,
+                label: tasting_sauce.label.clone(),
+                sauce_details: sauce_details.get(&tasting_sauce.sauce).cloned(),
+                scorecard_count: sauce_scorecards.len(),
+                axis_averages: axis_averages(axes, &by_axis),
+            }
+        })
+        .collect();
+
+    let overall = |result: &SauceResult| {
+        result
+            .axis_averages
+            .iter()
+            .find(|average| average.name == OVERALL_AXIS_NAME)
+            .map_or(f64::NEG_INFINITY, |average| average.average)
+    };
+    results.sort_by(|a, b| overall(b).total_cmp(&overall(a)));
+
+    results
+}
+
+async fn session_response(
+    conn: &DatabaseConnection,
+    session: entity::tasting_session::Model,
+    user_id: i32,
+) -> Result<SessionResponse> {
+    let revealed = session.revealed_at.is_some();
+
+    let host_username = User::find_by_id(session.host)
+        .one(conn)
+        .await?
+        .map(|user| user.username)
+        .unwrap_or_default();
+
+    let participant_count = TastingParticipant::find()
+        .filter(entity::tasting_participant::Column::Session.eq(session.id))
+        .all(conn)
+        .await?
+        .len();
+
+    let tasting_sauces = session_sauces(conn, session.id).await?;
+    let all_scorecards = scorecards(conn, &tasting_sauces, None).await?;
+
+    let usernames: HashMap<i32, String> = User::find()
+        .filter(entity::user::Column::Id.is_in(all_scorecards.iter().map(|s| s.scorecard.user)))
+        .all(conn)
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user.username))
+        .collect();
+
+    let submissions = all_scorecards
+        .iter()
+        .map(|s| Submission {
+            user: s.scorecard.user,
+            username: usernames
+                .get(&s.scorecard.user)
+                .cloned()
+                .unwrap_or_default(),
+            tasting_sauce: s.scorecard.tasting_sauce,
+        })
+        .collect();
+
+    let sauce_details: HashMap<i32, entity::sauce::Model> = if revealed {
+        Sauce::find()
+            .filter(entity::sauce::Column::Id.is_in(tasting_sauces.iter().map(|sauce| sauce.sauce)))
+            .all(conn)
+            .await?
+            .into_iter()
+            .map(|sauce| (sauce.id, sauce))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+
+    let results = if revealed {
+        let axes = RatingAxis::find().all(conn).await?;
+        Some(session_results(
+            &tasting_sauces,
+            &sauce_details,
+            &all_scorecards,
+            &axes,
+        ))
+    } else {
+        None
+    };
+
+    Ok(SessionResponse {
+        host_username,
+        is_host: session.host == user_id,
+        participant_count,
+        submissions,
+        scorecards: all_scorecards
+            .into_iter()
+            .filter(|s| s.scorecard.user == user_id)
+            .collect(),
+        sauces: tasting_sauces
+            .into_iter()
+            .map(|tasting_sauce| TastingSauceResponse {
+                id: tasting_sauce.id,
+                sauce_details: sauce_details.get(&tasting_sauce.sauce).cloned(),
+                label: tasting_sauce.label,
+            })
+            .collect(),
+        results,
+        session,
+    })
+}
+
+/// Add the user to the session if they aren't already in it, returning whether they were added
+async fn add_participant(
+    conn: &impl ConnectionTrait,
+    session_id: i32,
+    user_id: i32,
+) -> Result<bool> {
+    let existing = TastingParticipant::find_by_id((session_id, user_id))
+        .one(conn)
+        .await?;
+
+    if existing.is_some() {
+        return Ok(false);
+    }
+
+    entity::tasting_participant::ActiveModel {
+        session: Set(session_id),
+        user: Set(user_id),
+        joined_at: Set(Utc::now()),
+    }
+    .insert(conn)
+    .await?;
+
+    Ok(true)
+}
+
+/// Find a session the user is taking part in
+pub async fn find_joined_session(
+    conn: &DatabaseConnection,
+    auth: &AuthenticatedUser,
+    session_id: i32,
+) -> Result<entity::tasting_session::Model> {
+    TastingParticipant::find_by_id((session_id, auth.user_id))
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    TastingSession::find_by_id(session_id)
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)
+}
+
+#[derive(Debug, Deserialize)]
+struct NewSession {
+    name: String,
+    sauces: Vec<i32>,
+}
+
+/// Start a new session hosted by the caller. The sauces are shuffled before being given their
+/// labels so that the order they were picked in doesn't give anything away.
+async fn create_session(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Json(new): Json<NewSession>,
+) -> Result<impl IntoResponse> {
+    let mut sauce_ids = new.sauces;
+    sauce_ids.sort_unstable();
+    sauce_ids.dedup();
+
+    if new.name.trim().is_empty() {
+        return Err(Error::BadRequest(
+            "Session name must not be empty".to_string(),
+        ));
+    }
+    if sauce_ids.is_empty() || sauce_ids.len() > MAX_SESSION_SAUCES {
+        return Err(Error::BadRequest(format!(
+            "A session must have between 1 and {MAX_SESSION_SAUCES} sauces"
+        )));
+    }
+
+    let found = Sauce::find()
+        .filter(entity::sauce::Column::Id.is_in(sauce_ids.clone()))
+        .filter(entity::sauce::Column::HiddenAt.is_null())
+        .all(conn)
+        .await?;
+    if found.len() != sauce_ids.len() {
+        return Err(Error::NotFound);
+    }
+
+    sauce_ids.shuffle(&mut rand::thread_rng());
+
+    let txn = conn.begin().await?;
+    let session = entity::tasting_session::ActiveModel {
+        host: Set(auth.user_id),
+        name: Set(new.name),
+        join_code: Set(unused_join_code(&txn).await?),
+        created_at: Set(Utc::now()),
+        revealed_at: Set(None),
+        current_sauce: Set(None),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+
+    for (index, sauce_id) in sauce_ids.into_iter().enumerate() {
+        entity::tasting_sauce::ActiveModel {
+            session: Set(session.id),
+            sauce: Set(sauce_id),
+            label: Set(sauce_label(index)),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+    }
+
+    add_participant(&txn, session.id, auth.user_id).await?;
+    txn.commit().await?;
+
+    Ok(Json(session_response(conn, session, auth.user_id).await?))
+}
+
+#[derive(Debug, Deserialize)]
+struct JoinParams {
+    join_code: String,
+}
+
+async fn join_session(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(events): Extension<TastingEvents>,
+    Json(params): Json<JoinParams>,
+) -> Result<impl IntoResponse> {
+    let session = TastingSession::find()
+        .filter(
+            entity::tasting_session::Column::JoinCode.eq(params.join_code.trim().to_uppercase()),
+        )
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    if session.revealed_at.is_some() {
+        return Err(Error::BadRequest(
+            "This session has already been revealed".to_string(),
+        ));
+    }
+
+    if add_participant(conn, session.id, auth.user_id).await? {
+        let user = User::find_by_id(auth.user_id)
+            .one(conn)
+            .await?
+            .ok_or(Error::Unauthorized)?;
+
+        events.publish(
+            session.id,
+            TastingEvent::Joined {
+                user: user.id,
+                username: user.username,
+            },
+        );
+    }
+
+    Ok(Json(session_response(conn, session, auth.user_id).await?))
+}
+
+/// Sessions the caller has joined, most recent first
+async fn session_list(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+) -> Result<impl IntoResponse> {
+    let joined = sea_orm::sea_query::Query::select()
+        .column(entity::tasting_participant::Column::Session)
+        .from(TastingParticipant)
+        .and_where(entity::tasting_participant::Column::User.eq(auth.user_id))
+        .to_owned();
+
+    let sessions = TastingSession::find()
+        .filter(entity::tasting_session::Column::Id.in_subquery(joined))
+        .order_by_desc(entity::tasting_session::Column::CreatedAt)
+        .all(conn)
+        .await?;
+
+    Ok(Json(sessions))
+}
+
+async fn get_session(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Path(session_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let session = find_joined_session(conn, &auth, session_id).await?;
+    Ok(Json(session_response(conn, session, auth.user_id).await?))
+}
+
+#[derive(Debug, Deserialize)]
+struct ScorecardParams {
+    notes: Option<String>,
+    ratings: Vec<AxisRating>,
+}
+
+/// Create or replace the caller's scorecard for one of the session's sauces
+async fn submit_scorecard(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(events): Extension<TastingEvents>,
+    Extension(screening): Extension<Screening>,
+    Path((session_id, tasting_sauce_id)): Path<(i32, i32)>,
+    Json(params): Json<ScorecardParams>,
+) -> Result<impl IntoResponse> {
+    use entity::tasting_scorecard::Column;
+
+    let session = find_joined_session(conn, &auth, session_id).await?;
+    if session.revealed_at.is_some() {
+        return Err(Error::BadRequest(
+            "Scorecards can't be changed after the session has been revealed".to_string(),
+        ));
+    }
+
+    let tasting_sauce = TastingSauce::find_by_id(tasting_sauce_id)
+        .filter(entity::tasting_sauce::Column::Session.eq(session.id))
+        .one(conn)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    validate_ratings(conn, &params.ratings).await?;
+    // Notes become review text once the session is revealed. Anything that should be held for
+    // moderation is held then, when there's a review to hold.
+    screening
+        .check(conn, auth.user_id, params.notes.as_deref())
+        .await?;
+
+    let txn = conn.begin().await?;
+    let existing = TastingScorecard::find()
+        .filter(Column::TastingSauce.eq(tasting_sauce.id))
+        .filter(Column::User.eq(auth.user_id))
+        .one(&txn)
+        .await?;
+
+    let scorecard = match existing {
+        Some(existing) => {
+            TastingScorecardRating::delete_many()
+                .filter(entity::tasting_scorecard_rating::Column::Scorecard.eq(existing.id))
+                .exec(&txn)
+                .await?;
+
+            let mut scorecard: entity::tasting_scorecard::ActiveModel = existing.into();
+            scorecard.notes = Set(params.notes);
+            scorecard.updated_at = Set(Utc::now());
+            scorecard.update(&txn).await?
+        }
+        None => {
+            entity::tasting_scorecard::ActiveModel {
+                tasting_sauce: Set(tasting_sauce.id),
+                user: Set(auth.user_id),
+                notes: Set(params.notes),
+                review: Set(None),
+                updated_at: Set(Utc::now()),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await?
+        }
+    };
+
+    for rating in &params.ratings {
+        entity::tasting_scorecard_rating::ActiveModel {
+            scorecard: Set(scorecard.id),
+            rating_axis: Set(rating.rating_axis),
+            rating: Set(rating.rating),
+        }
+        .insert(&txn)
+        .await?;
+    }
+    txn.commit().await?;
+
+    let user = User::find_by_id(auth.user_id)
+        .one(conn)
+        .await?
+        .ok_or(Error::Unauthorized)?;
+    events.publish(
+        session.id,
+        TastingEvent::ScorecardSubmitted {
+            user: user.id,
+            username: user.username,
+            tasting_sauce: tasting_sauce.id,
+        },
+    );
+
+    Ok(Json(ScorecardResponse {
+        scorecard,
+        ratings: params.ratings,
+    }))
+}
+
+/// Move everyone on to the next sauce, in label order. Only the host can do this.
+async fn advance_session(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(events): Extension<TastingEvents>,
+    Path(session_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let session = find_joined_session(conn, &auth, session_id).await?;
+    if session.host != auth.user_id {
+        return Err(Error::Forbidden);
+    }
+    if session.revealed_at.is_some() {
+        return Err(Error::BadRequest(
+            "This session has already been revealed".to_string(),
+        ));
+    }
+
+    let tasting_sauces = session_sauces(conn, session.id).await?;
+    let next_index = match session.current_sauce {
+        Some(current) => tasting_sauces
+            .iter()
+            .position(|sauce| sauce.id == current)
+            .map_or(0, |index| index + 1),
+        None => 0,
+    };
+    let next = tasting_sauces
+        .get(next_index)
+        .ok_or_else(|| Error::BadRequest("There are no more sauces to move on to".to_string()))?;
+
+    let mut session: entity::tasting_session::ActiveModel = session.into();
+    session.current_sauce = Set(Some(next.id));
+    let session = session.update(conn).await?;
+
+    events.publish(
+        session.id,
+        TastingEvent::Advanced {
+            tasting_sauce: next.id,
+            label: next.label.clone(),
+        },
+    );
+
+    Ok(Json(session_response(conn, session, auth.user_id).await?))
+}
+
+/// Reveal which sauce is behind each label, turning every scorecard into a normal review of that
+/// sauce. Only the host can do this, and only once.
+async fn reveal_session(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(events): Extension<TastingEvents>,
+    Extension(screening): Extension<Screening>,
+    Path(session_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let session = find_joined_session(conn, &auth, session_id).await?;
+    if session.host != auth.user_id {
+        return Err(Error::Forbidden);
+    }
+    if session.revealed_at.is_some() {
+        return Err(Error::BadRequest(
+            "This session has already been revealed".to_string(),
+        ));
+    }
+
+    let txn = conn.begin().await?;
+    let now = Utc::now();
+
+    let tasting_sauces = session_sauces(&txn, session.id).await?;
+    for scorecard in scorecards(&txn, &tasting_sauces, None).await? {
+        if scorecard.ratings.is_empty() && scorecard.scorecard.notes.is_none() {
+            continue;
+        }
+
+        let sauce = tasting_sauces
+            .iter()
+            .find(|sauce| sauce.id == scorecard.scorecard.tasting_sauce)
+            .map(|sauce| sauce.sauce)
+            .ok_or(Error::NotFound)?;
+
+        // The notes were screened when they were saved, but the word lists may have changed since.
+        // Rather than failing the whole reveal, anything that would now be rejected is held.
+        let held = match scorecard.scorecard.notes.as_deref() {
+            Some(notes) => match screening.check_edit(notes) {
+                Ok(held) => held,
+                Err(Error::ContentRejected(reason)) => Some(reason),
+                Err(e) => return Err(e),
+            },
+            None => None,
+        };
+
+        let review = insert_review(
+            &txn,
+            entity::review::ActiveModel {
+                sauce: Set(sauce),
+                user: Set(scorecard.scorecard.user),
+                timestamp: Set(now),
+                text: Set(scorecard.scorecard.notes.clone()),
+                ..Default::default()
+            },
+            &scorecard.ratings,
+        )
+        .await?;
+        if let Some(reason) = &held {
+            hold_for_moderation(&txn, ModerationTarget::Review, review.id, &reason.message).await?;
+        }
+
+        let mut scorecard: entity::tasting_scorecard::ActiveModel = scorecard.scorecard.into();
+        scorecard.review = Set(Some(review.id));
+        scorecard.update(&txn).await?;
+    }
+
+    let mut session: entity::tasting_session::ActiveModel = session.into();
+    session.revealed_at = Set(Some(now));
+    let session = session.update(&txn).await?;
+    txn.commit().await?;
+
+    events.publish(session.id, TastingEvent::Revealed);
+    events.close(session.id);
+
+    Ok(Json(session_response(conn, session, auth.user_id).await?))
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/tasting", get(session_list).post(create_session))
+        .route("/tasting/join", post(join_session))
+        .route("/tasting/:session_id", get(get_session))
+        .route(
+            "/tasting/:session_id/sauce/:tasting_sauce_id/scorecard",
+            put(submit_scorecard),
+        )
+        .route("/tasting/:session_id/advance", post(advance_session))
+        .route("/tasting/:session_id/reveal", post(reveal_session))
+}
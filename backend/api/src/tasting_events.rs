@@ -0,0 +1,155 @@
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use axum::{
+    extract::Path,
+    response::{
+        sse::{Event, KeepAlive},
+        IntoResponse, Sse,
+    },
+    routing::get,
+    Extension, Router,
+};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
+
+use crate::{
+    auth::AuthenticatedUser,
+    error::{Error, Result},
+    tasting::find_joined_session,
+};
+
+/// How many events a slow subscriber can fall behind by before it starts missing them
+const CHANNEL_CAPACITY: usize = 64;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TastingEvent {
+    Joined {
+        user: i32,
+        username: String,
+    },
+    ScorecardSubmitted {
+        user: i32,
+        username: String,
+        tasting_sauce: i32,
+    },
+    Advanced {
+        tasting_sauce: i32,
+        label: String,
+    },
+    Revealed,
+}
+
+impl TastingEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            TastingEvent::Joined { .. } => "joined",
+            TastingEvent::ScorecardSubmitted { .. } => "scorecard_submitted",
+            TastingEvent::Advanced { .. } => "advanced",
+            TastingEvent::Revealed => "revealed",
+        }
+    }
+}
+
+/// Fans tasting session events out to everyone listening to that session
+#[derive(Debug, Clone, Default)]
+pub struct TastingEvents {
+    channels: Arc<Mutex<HashMap<i32, broadcast::Sender<TastingEvent>>>>,
+}
+
+impl TastingEvents {
+    fn subscribe(&self, session_id: i32) -> Subscription {
+        let mut channels = self.channels.lock().unwrap();
+        let receiver = channels
+            .entry(session_id)
+            .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0)
+            .subscribe();
+
+        Subscription {
+            events: BroadcastStream::new(receiver),
+            channels: self.clone(),
+            session_id,
+        }
+    }
+
+    /// Send an event to everyone currently listening to the session. Nobody listening isn't an
+    /// error, the event is just dropped.
+    pub fn publish(&self, session_id: i32, event: TastingEvent) {
+        let mut channels = self.channels.lock().unwrap();
+        if let Some(sender) = channels.get(&session_id) {
+            if sender.send(event).is_err() {
+                // Every subscriber has gone away
+                channels.remove(&session_id);
+            }
+        }
+    }
+
+    /// Stop sending events for a session that's over. Anyone still listening gets whatever was
+    /// already sent, then their stream ends.
+    pub fn close(&self, session_id: i32) {
+        self.channels.lock().unwrap().remove(&session_id);
+    }
+}
+
+/// One listener's events for a session, which forgets about the session's channel when the last
+/// listener goes away
+struct Subscription {
+    events: BroadcastStream<TastingEvent>,
+    channels: TastingEvents,
+    session_id: i32,
+}
+
+impl Stream for Subscription {
+    type Item = <BroadcastStream<TastingEvent> as Stream>::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.events).poll_next(cx)
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let mut channels = self.channels.channels.lock().unwrap();
+        // This subscription's receiver hasn't been dropped yet, so it's still counted
+        let last = channels
+            .get(&self.session_id)
+            .is_some_and(|sender| sender.receiver_count() <= 1);
+        if last {
+            channels.remove(&self.session_id);
+        }
+    }
+}
+
+async fn session_events(
+    auth: AuthenticatedUser,
+    Extension(ref conn): Extension<DatabaseConnection>,
+    Extension(events): Extension<TastingEvents>,
+    Path(session_id): Path<i32>,
+) -> Result<impl IntoResponse> {
+    let session = find_joined_session(conn, &auth, session_id).await?;
+    // Nothing more happens once a session is revealed
+    if session.revealed_at.is_some() {
+        return Err(Error::BadRequest(
+            "This session has already been revealed".to_string(),
+        ));
+    }
+
+    let stream = events.subscribe(session.id).filter_map(|event| {
+        // Subscribers that lag behind skip the events they missed rather than being disconnected
+        let event = event.ok()?;
+        Some(Event::default().event(event.name()).json_data(&event))
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+pub fn router() -> Router {
+    Router::new().route("/tasting/:session_id/events", get(session_events))
+}
@@ -2,12 +2,38 @@
 
 pub mod prelude;
 
+pub mod activity;
+pub mod catalog_image;
+pub mod data_export;
+pub mod follow;
 pub mod manufacturer;
+pub mod moderation_action;
+pub mod pepper;
+pub mod price_observation;
 pub mod rating_axis;
+pub mod report;
+pub mod reserved_username;
+pub mod retailer;
 pub mod review;
+pub mod review_comment;
+pub mod review_image;
 pub mod review_rating;
+pub mod review_vote;
 pub mod sauce;
+pub mod sauce_barcode;
+pub mod sauce_batch;
+pub mod sauce_list;
+pub mod sauce_list_entry;
+pub mod sauce_pepper;
+pub mod sea_orm_active_enums;
 pub mod seaql_migrations;
+pub mod shelf_entry;
+pub mod tasting_participant;
+pub mod tasting_sauce;
+pub mod tasting_scorecard;
+pub mod tasting_scorecard_rating;
+pub mod tasting_session;
 pub mod used_nonce;
 pub mod user;
+pub mod user_block;
 pub mod user_google_login;
@@ -9,12 +9,17 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
     pub name: String,
+    pub hidden_at: Option<DateTimeUtc>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::sauce::Entity")]
     Sauce,
+    #[sea_orm(has_many = "super::activity::Entity")]
+    Activity,
+    #[sea_orm(has_many = "super::catalog_image::Entity")]
+    CatalogImage,
 }
 
 impl Related<super::sauce::Entity> for Entity {
@@ -23,4 +28,16 @@ impl Related<super::sauce::Entity> for Entity {
     }
 }
 
+impl Related<super::activity::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Activity.def()
+    }
+}
+
+impl Related<super::catalog_image::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CatalogImage.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -1,10 +1,35 @@
 //! SeaORM Entity. Generated by sea-orm-codegen 0.9.2
 
+pub use super::activity::Entity as Activity;
+pub use super::catalog_image::Entity as CatalogImage;
+pub use super::data_export::Entity as DataExport;
+pub use super::follow::Entity as Follow;
 pub use super::manufacturer::Entity as Manufacturer;
+pub use super::moderation_action::Entity as ModerationAction;
+pub use super::pepper::Entity as Pepper;
+pub use super::price_observation::Entity as PriceObservation;
 pub use super::rating_axis::Entity as RatingAxis;
+pub use super::report::Entity as Report;
+pub use super::reserved_username::Entity as ReservedUsername;
+pub use super::retailer::Entity as Retailer;
 pub use super::review::Entity as Review;
+pub use super::review_comment::Entity as ReviewComment;
+pub use super::review_image::Entity as ReviewImage;
 pub use super::review_rating::Entity as ReviewRating;
+pub use super::review_vote::Entity as ReviewVote;
 pub use super::sauce::Entity as Sauce;
+pub use super::sauce_barcode::Entity as SauceBarcode;
+pub use super::sauce_batch::Entity as SauceBatch;
+pub use super::sauce_list::Entity as SauceList;
+pub use super::sauce_list_entry::Entity as SauceListEntry;
+pub use super::sauce_pepper::Entity as SaucePepper;
+pub use super::shelf_entry::Entity as ShelfEntry;
+pub use super::tasting_participant::Entity as TastingParticipant;
+pub use super::tasting_sauce::Entity as TastingSauce;
+pub use super::tasting_scorecard::Entity as TastingScorecard;
+pub use super::tasting_scorecard_rating::Entity as TastingScorecardRating;
+pub use super::tasting_session::Entity as TastingSession;
 pub use super::used_nonce::Entity as UsedNonce;
 pub use super::user::Entity as User;
+pub use super::user_block::Entity as UserBlock;
 pub use super::user_google_login::Entity as UserGoogleLogin;
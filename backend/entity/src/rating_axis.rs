@@ -19,6 +19,8 @@ pub struct Model {
 pub enum Relation {
     #[sea_orm(has_many = "super::review_rating::Entity")]
     ReviewRating,
+    #[sea_orm(has_many = "super::tasting_scorecard_rating::Entity")]
+    TastingScorecardRating,
 }
 
 impl Related<super::review_rating::Entity> for Entity {
@@ -27,4 +29,10 @@ impl Related<super::review_rating::Entity> for Entity {
     }
 }
 
+impl Related<super::tasting_scorecard_rating::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TastingScorecardRating.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
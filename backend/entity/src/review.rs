@@ -12,6 +12,8 @@ pub struct Model {
     pub user: i32,
     pub timestamp: DateTimeUtc,
     pub text: Option<String>,
+    pub hidden_at: Option<DateTimeUtc>,
+    pub batch: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -34,6 +36,16 @@ pub enum Relation {
     Sauce,
     #[sea_orm(has_many = "super::review_rating::Entity")]
     ReviewRating,
+    #[sea_orm(has_many = "super::tasting_scorecard::Entity")]
+    TastingScorecard,
+    #[sea_orm(has_many = "super::activity::Entity")]
+    Activity,
+    #[sea_orm(has_many = "super::review_comment::Entity")]
+    ReviewComment,
+    #[sea_orm(has_many = "super::review_vote::Entity")]
+    ReviewVote,
+    #[sea_orm(has_many = "super::review_image::Entity")]
+    ReviewImage,
 }
 
 impl Related<super::user::Entity> for Entity {
@@ -54,4 +66,34 @@ impl Related<super::review_rating::Entity> for Entity {
     }
 }
 
+impl Related<super::tasting_scorecard::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TastingScorecard.def()
+    }
+}
+
+impl Related<super::activity::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Activity.def()
+    }
+}
+
+impl Related<super::review_comment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ReviewComment.def()
+    }
+}
+
+impl Related<super::review_vote::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ReviewVote.def()
+    }
+}
+
+impl Related<super::review_image::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ReviewImage.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
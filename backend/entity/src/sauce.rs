@@ -10,6 +10,7 @@ pub struct Model {
     pub id: i32,
     pub name: String,
     pub manufacturer: i32,
+    pub hidden_at: Option<DateTimeUtc>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -24,6 +25,24 @@ pub enum Relation {
     Manufacturer,
     #[sea_orm(has_many = "super::review::Entity")]
     Review,
+    #[sea_orm(has_many = "super::sauce_pepper::Entity")]
+    SaucePepper,
+    #[sea_orm(has_many = "super::shelf_entry::Entity")]
+    ShelfEntry,
+    #[sea_orm(has_many = "super::sauce_list_entry::Entity")]
+    SauceListEntry,
+    #[sea_orm(has_many = "super::tasting_sauce::Entity")]
+    TastingSauce,
+    #[sea_orm(has_many = "super::activity::Entity")]
+    Activity,
+    #[sea_orm(has_many = "super::catalog_image::Entity")]
+    CatalogImage,
+    #[sea_orm(has_many = "super::sauce_barcode::Entity")]
+    SauceBarcode,
+    #[sea_orm(has_many = "super::sauce_batch::Entity")]
+    SauceBatch,
+    #[sea_orm(has_many = "super::price_observation::Entity")]
+    PriceObservation,
 }
 
 impl Related<super::manufacturer::Entity> for Entity {
@@ -38,4 +57,67 @@ impl Related<super::review::Entity> for Entity {
     }
 }
 
+impl Related<super::sauce_pepper::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SaucePepper.def()
+    }
+}
+
+impl Related<super::pepper::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::sauce_pepper::Relation::Pepper.def()
+    }
+    fn via() -> Option<RelationDef> {
+        Some(super::sauce_pepper::Relation::Sauce.def().rev())
+    }
+}
+
+impl Related<super::shelf_entry::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ShelfEntry.def()
+    }
+}
+
+impl Related<super::sauce_list_entry::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SauceListEntry.def()
+    }
+}
+
+impl Related<super::tasting_sauce::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TastingSauce.def()
+    }
+}
+
+impl Related<super::activity::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Activity.def()
+    }
+}
+
+impl Related<super::catalog_image::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CatalogImage.def()
+    }
+}
+
+impl Related<super::sauce_barcode::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SauceBarcode.def()
+    }
+}
+
+impl Related<super::sauce_batch::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SauceBatch.def()
+    }
+}
+
+impl Related<super::price_observation::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PriceObservation.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -1,5 +1,6 @@
 //! SeaORM Entity. Generated by sea-orm-codegen 0.9.2
 
+use super::sea_orm_active_enums::ProfilePrivacy;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -9,6 +10,11 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
     pub username: String,
+    pub taste_profile_public: bool,
+    pub privacy: ProfilePrivacy,
+    pub username_changed_at: Option<DateTimeUtc>,
+    pub is_moderator: bool,
+    pub hidden_at: Option<DateTimeUtc>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -19,6 +25,36 @@ pub enum Relation {
     UserGoogleLogin,
     #[sea_orm(has_many = "super::review::Entity")]
     Review,
+    #[sea_orm(has_many = "super::reserved_username::Entity")]
+    ReservedUsername,
+    #[sea_orm(has_many = "super::data_export::Entity")]
+    DataExport,
+    #[sea_orm(has_many = "super::shelf_entry::Entity")]
+    ShelfEntry,
+    #[sea_orm(has_many = "super::sauce_list::Entity")]
+    SauceList,
+    #[sea_orm(has_many = "super::tasting_session::Entity")]
+    TastingSession,
+    #[sea_orm(has_many = "super::tasting_participant::Entity")]
+    TastingParticipant,
+    #[sea_orm(has_many = "super::tasting_scorecard::Entity")]
+    TastingScorecard,
+    #[sea_orm(has_many = "super::activity::Entity")]
+    Activity,
+    #[sea_orm(has_many = "super::review_comment::Entity")]
+    ReviewComment,
+    #[sea_orm(has_many = "super::review_vote::Entity")]
+    ReviewVote,
+    #[sea_orm(has_many = "super::report::Entity")]
+    Report,
+    #[sea_orm(has_many = "super::catalog_image::Entity")]
+    CatalogImage,
+    #[sea_orm(has_many = "super::sauce_barcode::Entity")]
+    SauceBarcode,
+    #[sea_orm(has_many = "super::sauce_batch::Entity")]
+    SauceBatch,
+    #[sea_orm(has_many = "super::price_observation::Entity")]
+    PriceObservation,
 }
 
 impl Related<super::used_nonce::Entity> for Entity {
@@ -39,4 +75,94 @@ impl Related<super::review::Entity> for Entity {
     }
 }
 
+impl Related<super::reserved_username::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ReservedUsername.def()
+    }
+}
+
+impl Related<super::data_export::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DataExport.def()
+    }
+}
+
+impl Related<super::shelf_entry::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ShelfEntry.def()
+    }
+}
+
+impl Related<super::sauce_list::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SauceList.def()
+    }
+}
+
+impl Related<super::tasting_session::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TastingSession.def()
+    }
+}
+
+impl Related<super::tasting_participant::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TastingParticipant.def()
+    }
+}
+
+impl Related<super::tasting_scorecard::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TastingScorecard.def()
+    }
+}
+
+impl Related<super::activity::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Activity.def()
+    }
+}
+
+impl Related<super::review_comment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ReviewComment.def()
+    }
+}
+
+impl Related<super::review_vote::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ReviewVote.def()
+    }
+}
+
+impl Related<super::report::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Report.def()
+    }
+}
+
+impl Related<super::catalog_image::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CatalogImage.def()
+    }
+}
+
+impl Related<super::sauce_barcode::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SauceBarcode.def()
+    }
+}
+
+impl Related<super::sauce_batch::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SauceBatch.def()
+    }
+}
+
+impl Related<super::price_observation::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PriceObservation.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -3,6 +3,26 @@ pub use sea_orm_migration::prelude::*;
 mod m001_create_user_tables;
 mod m002_create_sauce_tables;
 mod m003_create_ratings_tables;
+mod m004_create_pepper_tables;
+mod m005_add_taste_profile_visibility;
+mod m006_add_user_privacy;
+mod m007_add_username_changes;
+mod m008_create_data_export_table;
+mod m009_create_shelf_table;
+mod m010_create_sauce_list_tables;
+mod m011_create_tasting_tables;
+mod m012_add_tasting_progress;
+mod m013_create_follow_and_activity_tables;
+mod m014_create_review_comment_table;
+mod m015_create_review_vote_table;
+mod m016_create_moderation_tables;
+mod m017_create_user_block_table;
+mod m018_create_review_image_table;
+mod m019_create_catalog_image_table;
+mod m020_create_sauce_barcode_table;
+mod m021_create_sauce_batch_table;
+mod m022_create_price_tables;
+mod util;
 
 pub struct Migrator;
 
@@ -13,6 +33,25 @@ impl MigratorTrait for Migrator {
             Box::new(m001_create_user_tables::Migration),
             Box::new(m002_create_sauce_tables::Migration),
             Box::new(m003_create_ratings_tables::Migration),
+            Box::new(m004_create_pepper_tables::Migration),
+            Box::new(m005_add_taste_profile_visibility::Migration),
+            Box::new(m006_add_user_privacy::Migration),
+            Box::new(m007_add_username_changes::Migration),
+            Box::new(m008_create_data_export_table::Migration),
+            Box::new(m009_create_shelf_table::Migration),
+            Box::new(m010_create_sauce_list_tables::Migration),
+            Box::new(m011_create_tasting_tables::Migration),
+            Box::new(m012_add_tasting_progress::Migration),
+            Box::new(m013_create_follow_and_activity_tables::Migration),
+            Box::new(m014_create_review_comment_table::Migration),
+            Box::new(m015_create_review_vote_table::Migration),
+            Box::new(m016_create_moderation_tables::Migration),
+            Box::new(m017_create_user_block_table::Migration),
+            Box::new(m018_create_review_image_table::Migration),
+            Box::new(m019_create_catalog_image_table::Migration),
+            Box::new(m020_create_sauce_barcode_table::Migration),
+            Box::new(m021_create_sauce_batch_table::Migration),
+            Box::new(m022_create_price_tables::Migration),
         ]
     }
 }
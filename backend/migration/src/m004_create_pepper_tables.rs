@@ -0,0 +1,92 @@
+use sea_orm_migration::{prelude::*, sea_query::Table};
+
+use crate::m002_create_sauce_tables::Sauce;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "004_create_pepper_tables"
+    }
+}
+
+#[derive(Iden)]
+pub enum Pepper {
+    Table,
+    Id,
+    Name,
+}
+
+#[derive(Iden)]
+pub enum SaucePepper {
+    Table,
+    Sauce,
+    Pepper,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Pepper::Table)
+                    .col(
+                        ColumnDef::new(Pepper::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Pepper::Name)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(SaucePepper::Table)
+                    .col(ColumnDef::new(SaucePepper::Sauce).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(SaucePepper::Table, SaucePepper::Sauce)
+                            .to(Sauce::Table, Sauce::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(SaucePepper::Pepper).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(SaucePepper::Table, SaucePepper::Pepper)
+                            .to(Pepper::Table, Pepper::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(SaucePepper::Sauce)
+                            .col(SaucePepper::Pepper),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SaucePepper::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Pepper::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
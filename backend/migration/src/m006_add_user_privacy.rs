@@ -0,0 +1,42 @@
+use sea_orm_migration::{prelude::*, sea_query::Table};
+
+use crate::util::drop_column;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "006_add_user_privacy"
+    }
+}
+
+#[derive(Iden)]
+pub enum User {
+    Table,
+    Privacy,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(
+                        ColumnDef::new(User::Privacy)
+                            .string()
+                            .not_null()
+                            .default("public"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        drop_column(manager, User::Table, User::Privacy).await
+    }
+}
@@ -0,0 +1,101 @@
+use sea_orm_migration::{prelude::*, sea_query::Table};
+
+use crate::util::drop_column;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "007_add_username_changes"
+    }
+}
+
+/// Reviews from deleted accounts can be reassigned to this user rather than being deleted. The
+/// brackets make it impossible for anyone to sign up with, or change to, this name.
+const DELETED_USER_USERNAME: &str = "[deleted]";
+
+#[derive(Iden)]
+pub enum User {
+    Table,
+    Id,
+    Username,
+    Privacy,
+    UsernameChangedAt,
+}
+
+#[derive(Iden)]
+pub enum ReservedUsername {
+    Table,
+    Username,
+    User,
+    ReservedUntil,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(ColumnDef::new(User::UsernameChangedAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReservedUsername::Table)
+                    .col(
+                        ColumnDef::new(ReservedUsername::Username)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ReservedUsername::User).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ReservedUsername::Table, ReservedUsername::User)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(ReservedUsername::ReservedUntil)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(User::Table)
+                    .columns([User::Username, User::Privacy])
+                    .values_panic([DELETED_USER_USERNAME.into(), "private".into()])
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(User::Table)
+                    .and_where(Expr::col(User::Username).eq(DELETED_USER_USERNAME))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ReservedUsername::Table).to_owned())
+            .await?;
+
+        drop_column(manager, User::Table, User::UsernameChangedAt).await
+    }
+}
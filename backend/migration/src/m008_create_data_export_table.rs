@@ -0,0 +1,65 @@
+use sea_orm_migration::{prelude::*, sea_query::Table};
+
+use crate::m001_create_user_tables::User;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "008_create_data_export_table"
+    }
+}
+
+#[derive(Iden)]
+pub enum DataExport {
+    Table,
+    Id,
+    User,
+    Status,
+    RequestedAt,
+    CompletedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DataExport::Table)
+                    .col(
+                        ColumnDef::new(DataExport::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(DataExport::User).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(DataExport::Table, DataExport::User)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(DataExport::Status).string().not_null())
+                    .col(
+                        ColumnDef::new(DataExport::RequestedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(DataExport::CompletedAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DataExport::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
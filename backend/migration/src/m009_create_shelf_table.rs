@@ -0,0 +1,101 @@
+use sea_orm_migration::{prelude::*, sea_query::Table};
+
+use crate::{m001_create_user_tables::User, m002_create_sauce_tables::Sauce};
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "009_create_shelf_table"
+    }
+}
+
+#[derive(Iden)]
+pub enum ShelfEntry {
+    Table,
+    Id,
+    User,
+    Sauce,
+    Status,
+    Quantity,
+    AcquiredOn,
+    Notes,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ShelfEntry::Table)
+                    .col(
+                        ColumnDef::new(ShelfEntry::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ShelfEntry::User).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ShelfEntry::Table, ShelfEntry::User)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(ShelfEntry::Sauce).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ShelfEntry::Table, ShelfEntry::Sauce)
+                            .to(Sauce::Table, Sauce::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(ShelfEntry::Status).string().not_null())
+                    .col(
+                        ColumnDef::new(ShelfEntry::Quantity)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(ColumnDef::new(ShelfEntry::AcquiredOn).date())
+                    .col(ColumnDef::new(ShelfEntry::Notes).string())
+                    .col(
+                        ColumnDef::new(ShelfEntry::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ShelfEntry::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-shelf_entry-user-sauce-status")
+                    .table(ShelfEntry::Table)
+                    .col(ShelfEntry::User)
+                    .col(ShelfEntry::Sauce)
+                    .col(ShelfEntry::Status)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ShelfEntry::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use sea_orm_migration::{prelude::*, sea_query::Table};
+
+use crate::{m001_create_user_tables::User, m002_create_sauce_tables::Sauce};
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "010_create_sauce_list_tables"
+    }
+}
+
+#[derive(Iden)]
+pub enum SauceList {
+    Table,
+    Id,
+    User,
+    Name,
+    Description,
+    Public,
+    ForkedFrom,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+pub enum SauceListEntry {
+    Table,
+    Id,
+    List,
+    Sauce,
+    Position,
+    Commentary,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SauceList::Table)
+                    .col(
+                        ColumnDef::new(SauceList::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SauceList::User).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(SauceList::Table, SauceList::User)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(SauceList::Name).string().not_null())
+                    .col(ColumnDef::new(SauceList::Description).string())
+                    .col(
+                        ColumnDef::new(SauceList::Public)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(ColumnDef::new(SauceList::ForkedFrom).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(SauceList::Table, SauceList::ForkedFrom)
+                            .to(SauceList::Table, SauceList::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .col(
+                        ColumnDef::new(SauceList::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SauceList::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(SauceListEntry::Table)
+                    .col(
+                        ColumnDef::new(SauceListEntry::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SauceListEntry::List).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(SauceListEntry::Table, SauceListEntry::List)
+                            .to(SauceList::Table, SauceList::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(SauceListEntry::Sauce).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(SauceListEntry::Table, SauceListEntry::Sauce)
+                            .to(Sauce::Table, Sauce::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(SauceListEntry::Position)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(SauceListEntry::Commentary).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-sauce_list_entry-list-sauce")
+                    .table(SauceListEntry::Table)
+                    .col(SauceListEntry::List)
+                    .col(SauceListEntry::Sauce)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SauceListEntry::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(SauceList::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,318 @@
+use sea_orm_migration::{prelude::*, sea_query::Table};
+
+use crate::{
+    m001_create_user_tables::User,
+    m002_create_sauce_tables::Sauce,
+    m003_create_ratings_tables::{RatingAxis, Review},
+};
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "011_create_tasting_tables"
+    }
+}
+
+#[derive(Iden)]
+pub enum TastingSession {
+    Table,
+    Id,
+    Host,
+    Name,
+    JoinCode,
+    CreatedAt,
+    RevealedAt,
+}
+
+#[derive(Iden)]
+pub enum TastingSauce {
+    Table,
+    Id,
+    Session,
+    Sauce,
+    Label,
+}
+
+#[derive(Iden)]
+pub enum TastingParticipant {
+    Table,
+    Session,
+    User,
+    JoinedAt,
+}
+
+#[derive(Iden)]
+pub enum TastingScorecard {
+    Table,
+    Id,
+    TastingSauce,
+    User,
+    Notes,
+    Review,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+pub enum TastingScorecardRating {
+    Table,
+    Scorecard,
+    RatingAxis,
+    Rating,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TastingSession::Table)
+                    .col(
+                        ColumnDef::new(TastingSession::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(TastingSession::Host).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TastingSession::Table, TastingSession::Host)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(TastingSession::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(TastingSession::JoinCode)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(TastingSession::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(TastingSession::RevealedAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(TastingSauce::Table)
+                    .col(
+                        ColumnDef::new(TastingSauce::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(TastingSauce::Session).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TastingSauce::Table, TastingSauce::Session)
+                            .to(TastingSession::Table, TastingSession::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(TastingSauce::Sauce).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TastingSauce::Table, TastingSauce::Sauce)
+                            .to(Sauce::Table, Sauce::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(TastingSauce::Label).string().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-tasting_sauce-session-sauce")
+                    .table(TastingSauce::Table)
+                    .col(TastingSauce::Session)
+                    .col(TastingSauce::Sauce)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(TastingParticipant::Table)
+                    .col(
+                        ColumnDef::new(TastingParticipant::Session)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TastingParticipant::Table, TastingParticipant::Session)
+                            .to(TastingSession::Table, TastingSession::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(TastingParticipant::User)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TastingParticipant::Table, TastingParticipant::User)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(TastingParticipant::JoinedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(TastingParticipant::Session)
+                            .col(TastingParticipant::User),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(TastingScorecard::Table)
+                    .col(
+                        ColumnDef::new(TastingScorecard::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(TastingScorecard::TastingSauce)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TastingScorecard::Table, TastingScorecard::TastingSauce)
+                            .to(TastingSauce::Table, TastingSauce::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(TastingScorecard::User).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TastingScorecard::Table, TastingScorecard::User)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(TastingScorecard::Notes).string())
+                    .col(ColumnDef::new(TastingScorecard::Review).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(TastingScorecard::Table, TastingScorecard::Review)
+                            .to(Review::Table, Review::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .col(
+                        ColumnDef::new(TastingScorecard::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-tasting_scorecard-tasting_sauce-user")
+                    .table(TastingScorecard::Table)
+                    .col(TastingScorecard::TastingSauce)
+                    .col(TastingScorecard::User)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(TastingScorecardRating::Table)
+                    .col(
+                        ColumnDef::new(TastingScorecardRating::Scorecard)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(
+                                TastingScorecardRating::Table,
+                                TastingScorecardRating::Scorecard,
+                            )
+                            .to(TastingScorecard::Table, TastingScorecard::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(TastingScorecardRating::RatingAxis)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(
+                                TastingScorecardRating::Table,
+                                TastingScorecardRating::RatingAxis,
+                            )
+                            .to(RatingAxis::Table, RatingAxis::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(TastingScorecardRating::Rating)
+                            .float()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(TastingScorecardRating::Scorecard)
+                            .col(TastingScorecardRating::RatingAxis),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(TastingScorecardRating::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(TastingScorecard::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(TastingParticipant::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(TastingSauce::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(TastingSession::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
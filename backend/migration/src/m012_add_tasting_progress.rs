@@ -0,0 +1,37 @@
+use sea_orm_migration::{prelude::*, sea_query::Table};
+
+use crate::util::drop_column;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "012_add_tasting_progress"
+    }
+}
+
+#[derive(Iden)]
+pub enum TastingSession {
+    Table,
+    CurrentSauce,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TastingSession::Table)
+                    .add_column(ColumnDef::new(TastingSession::CurrentSauce).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        drop_column(manager, TastingSession::Table, TastingSession::CurrentSauce).await
+    }
+}
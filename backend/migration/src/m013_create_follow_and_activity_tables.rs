@@ -0,0 +1,150 @@
+use sea_orm_migration::{prelude::*, sea_query::Table};
+
+use crate::{
+    m001_create_user_tables::User,
+    m002_create_sauce_tables::{Manufacturer, Sauce},
+    m003_create_ratings_tables::Review,
+    m010_create_sauce_list_tables::SauceList,
+};
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "013_create_follow_and_activity_tables"
+    }
+}
+
+#[derive(Iden)]
+pub enum Follow {
+    Table,
+    Follower,
+    Followee,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+pub enum Activity {
+    Table,
+    Id,
+    Actor,
+    Kind,
+    Review,
+    Sauce,
+    Manufacturer,
+    SauceList,
+    CreatedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Follow::Table)
+                    .col(ColumnDef::new(Follow::Follower).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Follow::Table, Follow::Follower)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Follow::Followee).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Follow::Table, Follow::Followee)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(Follow::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .primary_key(Index::create().col(Follow::Follower).col(Follow::Followee))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Activity::Table)
+                    .col(
+                        ColumnDef::new(Activity::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Activity::Actor).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Activity::Table, Activity::Actor)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Activity::Kind).string().not_null())
+                    .col(ColumnDef::new(Activity::Review).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Activity::Table, Activity::Review)
+                            .to(Review::Table, Review::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Activity::Sauce).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Activity::Table, Activity::Sauce)
+                            .to(Sauce::Table, Sauce::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Activity::Manufacturer).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Activity::Table, Activity::Manufacturer)
+                            .to(Manufacturer::Table, Manufacturer::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Activity::SauceList).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Activity::Table, Activity::SauceList)
+                            .to(SauceList::Table, SauceList::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(Activity::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-activity-actor")
+                    .table(Activity::Table)
+                    .col(Activity::Actor)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Activity::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Follow::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use sea_orm_migration::{prelude::*, sea_query::Table};
+
+use crate::{m001_create_user_tables::User, m003_create_ratings_tables::Review};
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "014_create_review_comment_table"
+    }
+}
+
+#[derive(Iden)]
+pub enum ReviewComment {
+    Table,
+    Id,
+    Review,
+    Parent,
+    Author,
+    Body,
+    CreatedAt,
+    EditedAt,
+    DeletedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReviewComment::Table)
+                    .col(
+                        ColumnDef::new(ReviewComment::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ReviewComment::Review).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ReviewComment::Table, ReviewComment::Review)
+                            .to(Review::Table, Review::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(ReviewComment::Parent).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ReviewComment::Table, ReviewComment::Parent)
+                            .to(ReviewComment::Table, ReviewComment::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(ReviewComment::Author).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ReviewComment::Table, ReviewComment::Author)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(ReviewComment::Body).string().not_null())
+                    .col(
+                        ColumnDef::new(ReviewComment::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ReviewComment::EditedAt).timestamp_with_time_zone())
+                    .col(ColumnDef::new(ReviewComment::DeletedAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-review_comment-review")
+                    .table(ReviewComment::Table)
+                    .col(ReviewComment::Review)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ReviewComment::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use sea_orm_migration::{prelude::*, sea_query::Table};
+
+use crate::{m001_create_user_tables::User, m003_create_ratings_tables::Review};
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "015_create_review_vote_table"
+    }
+}
+
+#[derive(Iden)]
+pub enum ReviewVote {
+    Table,
+    Review,
+    User,
+    Helpful,
+    CreatedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReviewVote::Table)
+                    .col(ColumnDef::new(ReviewVote::Review).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ReviewVote::Table, ReviewVote::Review)
+                            .to(Review::Table, Review::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(ReviewVote::User).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ReviewVote::Table, ReviewVote::User)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(ReviewVote::Helpful).boolean().not_null())
+                    .col(
+                        ColumnDef::new(ReviewVote::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(ReviewVote::Review)
+                            .col(ReviewVote::User),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ReviewVote::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
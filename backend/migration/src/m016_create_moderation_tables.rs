@@ -0,0 +1,226 @@
+use sea_orm_migration::{prelude::*, sea_query::Table};
+
+use crate::util::drop_column;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "016_create_moderation_tables"
+    }
+}
+
+#[derive(Iden)]
+pub enum User {
+    Table,
+    Id,
+    IsModerator,
+    HiddenAt,
+}
+
+#[derive(Iden)]
+pub enum Review {
+    Table,
+    HiddenAt,
+}
+
+#[derive(Iden)]
+pub enum ReviewComment {
+    Table,
+    HiddenAt,
+}
+
+#[derive(Iden)]
+pub enum Sauce {
+    Table,
+    HiddenAt,
+}
+
+#[derive(Iden)]
+pub enum Manufacturer {
+    Table,
+    HiddenAt,
+}
+
+#[derive(Iden)]
+pub enum ModerationAction {
+    Table,
+    Id,
+    Moderator,
+    Kind,
+    TargetKind,
+    TargetId,
+    TargetUser,
+    Note,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+pub enum Report {
+    Table,
+    Id,
+    Reporter,
+    TargetKind,
+    TargetId,
+    Reason,
+    Details,
+    Status,
+    CreatedAt,
+    ResolvedAt,
+    ResolvedBy,
+}
+
+async fn add_hidden_at<T, C>(manager: &SchemaManager<'_>, table: T, column: C) -> Result<(), DbErr>
+where
+    T: Iden + 'static,
+    C: Iden + 'static,
+{
+    manager
+        .alter_table(
+            Table::alter()
+                .table(table)
+                .add_column(ColumnDef::new(column).timestamp_with_time_zone())
+                .to_owned(),
+        )
+        .await
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(
+                        ColumnDef::new(User::IsModerator)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // sqlite only allows one column to be added per statement
+        add_hidden_at(manager, User::Table, User::HiddenAt).await?;
+        add_hidden_at(manager, Review::Table, Review::HiddenAt).await?;
+        add_hidden_at(manager, ReviewComment::Table, ReviewComment::HiddenAt).await?;
+        add_hidden_at(manager, Sauce::Table, Sauce::HiddenAt).await?;
+        add_hidden_at(manager, Manufacturer::Table, Manufacturer::HiddenAt).await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ModerationAction::Table)
+                    .col(
+                        ColumnDef::new(ModerationAction::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ModerationAction::Moderator).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ModerationAction::Table, ModerationAction::Moderator)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .col(ColumnDef::new(ModerationAction::Kind).string().not_null())
+                    .col(
+                        ColumnDef::new(ModerationAction::TargetKind)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ModerationAction::TargetId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ModerationAction::TargetUser).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ModerationAction::Table, ModerationAction::TargetUser)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .col(ColumnDef::new(ModerationAction::Note).string())
+                    .col(
+                        ColumnDef::new(ModerationAction::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Report::Table)
+                    .col(
+                        ColumnDef::new(Report::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Report::Reporter).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Report::Table, Report::Reporter)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .col(ColumnDef::new(Report::TargetKind).string().not_null())
+                    .col(ColumnDef::new(Report::TargetId).integer().not_null())
+                    .col(ColumnDef::new(Report::Reason).string().not_null())
+                    .col(ColumnDef::new(Report::Details).string())
+                    .col(ColumnDef::new(Report::Status).string().not_null())
+                    .col(
+                        ColumnDef::new(Report::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Report::ResolvedAt).timestamp_with_time_zone())
+                    .col(ColumnDef::new(Report::ResolvedBy).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Report::Table, Report::ResolvedBy)
+                            .to(ModerationAction::Table, ModerationAction::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-report-status")
+                    .table(Report::Table)
+                    .col(Report::Status)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Report::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ModerationAction::Table).to_owned())
+            .await?;
+
+        drop_column(manager, Manufacturer::Table, Manufacturer::HiddenAt).await?;
+        drop_column(manager, Sauce::Table, Sauce::HiddenAt).await?;
+        drop_column(manager, ReviewComment::Table, ReviewComment::HiddenAt).await?;
+        drop_column(manager, Review::Table, Review::HiddenAt).await?;
+        drop_column(manager, User::Table, User::HiddenAt).await?;
+        drop_column(manager, User::Table, User::IsModerator).await
+    }
+}
@@ -0,0 +1,68 @@
+use sea_orm_migration::{prelude::*, sea_query::Table};
+
+use crate::m001_create_user_tables::User;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "017_create_user_block_table"
+    }
+}
+
+#[derive(Iden)]
+pub enum UserBlock {
+    Table,
+    Blocker,
+    Blocked,
+    Kind,
+    CreatedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserBlock::Table)
+                    .col(ColumnDef::new(UserBlock::Blocker).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(UserBlock::Table, UserBlock::Blocker)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(UserBlock::Blocked).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(UserBlock::Table, UserBlock::Blocked)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(UserBlock::Kind).string().not_null())
+                    .col(
+                        ColumnDef::new(UserBlock::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(UserBlock::Blocker)
+                            .col(UserBlock::Blocked),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserBlock::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+use sea_orm_migration::{prelude::*, sea_query::Table};
+
+use crate::m003_create_ratings_tables::Review;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "018_create_review_image_table"
+    }
+}
+
+#[derive(Iden)]
+pub enum ReviewImage {
+    Table,
+    Id,
+    Review,
+    StorageKey,
+    ThumbnailKey,
+    ContentType,
+    Width,
+    Height,
+    CreatedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReviewImage::Table)
+                    .col(
+                        ColumnDef::new(ReviewImage::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ReviewImage::Review).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ReviewImage::Table, ReviewImage::Review)
+                            .to(Review::Table, Review::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(ReviewImage::StorageKey).string().not_null())
+                    .col(
+                        ColumnDef::new(ReviewImage::ThumbnailKey)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ReviewImage::ContentType).string().not_null())
+                    .col(ColumnDef::new(ReviewImage::Width).integer().not_null())
+                    .col(ColumnDef::new(ReviewImage::Height).integer().not_null())
+                    .col(
+                        ColumnDef::new(ReviewImage::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-review_image-review")
+                    .table(ReviewImage::Table)
+                    .col(ReviewImage::Review)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ReviewImage::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
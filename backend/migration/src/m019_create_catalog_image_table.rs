@@ -0,0 +1,122 @@
+use sea_orm_migration::{prelude::*, sea_query::Table};
+
+use crate::{
+    m001_create_user_tables::User,
+    m002_create_sauce_tables::{Manufacturer, Sauce},
+};
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "019_create_catalog_image_table"
+    }
+}
+
+/// Images of a sauce or a manufacturer. Exactly one of `sauce` and `manufacturer` is set.
+#[derive(Iden)]
+pub enum CatalogImage {
+    Table,
+    Id,
+    Sauce,
+    Manufacturer,
+    UploadedBy,
+    StorageKey,
+    ContentType,
+    Width,
+    Height,
+    IsCover,
+    HiddenAt,
+    CreatedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CatalogImage::Table)
+                    .col(
+                        ColumnDef::new(CatalogImage::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(CatalogImage::Sauce).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(CatalogImage::Table, CatalogImage::Sauce)
+                            .to(Sauce::Table, Sauce::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(CatalogImage::Manufacturer).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(CatalogImage::Table, CatalogImage::Manufacturer)
+                            .to(Manufacturer::Table, Manufacturer::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(CatalogImage::UploadedBy).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(CatalogImage::Table, CatalogImage::UploadedBy)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .col(ColumnDef::new(CatalogImage::StorageKey).string().not_null())
+                    .col(
+                        ColumnDef::new(CatalogImage::ContentType)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(CatalogImage::Width).integer().not_null())
+                    .col(ColumnDef::new(CatalogImage::Height).integer().not_null())
+                    .col(
+                        ColumnDef::new(CatalogImage::IsCover)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(ColumnDef::new(CatalogImage::HiddenAt).timestamp_with_time_zone())
+                    .col(
+                        ColumnDef::new(CatalogImage::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-catalog_image-sauce")
+                    .table(CatalogImage::Table)
+                    .col(CatalogImage::Sauce)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-catalog_image-manufacturer")
+                    .table(CatalogImage::Table)
+                    .col(CatalogImage::Manufacturer)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CatalogImage::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
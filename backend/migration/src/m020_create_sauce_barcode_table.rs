@@ -0,0 +1,79 @@
+use sea_orm_migration::{prelude::*, sea_query::Table};
+
+use crate::{m001_create_user_tables::User, m002_create_sauce_tables::Sauce};
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "020_create_sauce_barcode_table"
+    }
+}
+
+/// Barcodes printed on a sauce's bottles, stored as 13 digit EAN-13 codes
+#[derive(Iden)]
+pub enum SauceBarcode {
+    Table,
+    Code,
+    Sauce,
+    AddedBy,
+    CreatedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SauceBarcode::Table)
+                    .col(
+                        ColumnDef::new(SauceBarcode::Code)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SauceBarcode::Sauce).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(SauceBarcode::Table, SauceBarcode::Sauce)
+                            .to(Sauce::Table, Sauce::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(SauceBarcode::AddedBy).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(SauceBarcode::Table, SauceBarcode::AddedBy)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .col(
+                        ColumnDef::new(SauceBarcode::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-sauce_barcode-sauce")
+                    .table(SauceBarcode::Table)
+                    .col(SauceBarcode::Sauce)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SauceBarcode::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
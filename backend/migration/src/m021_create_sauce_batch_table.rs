@@ -0,0 +1,108 @@
+use sea_orm_migration::{prelude::*, sea_query::Table};
+
+use crate::{m001_create_user_tables::User, m002_create_sauce_tables::Sauce, util::drop_column};
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "021_create_sauce_batch_table"
+    }
+}
+
+/// A production run or recipe version of a sauce, as identified from its label
+#[derive(Iden)]
+pub enum SauceBatch {
+    Table,
+    Id,
+    Sauce,
+    LabelDate,
+    LotNumber,
+    NewRecipe,
+    AddedBy,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+pub enum Review {
+    Table,
+    Batch,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SauceBatch::Table)
+                    .col(
+                        ColumnDef::new(SauceBatch::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SauceBatch::Sauce).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(SauceBatch::Table, SauceBatch::Sauce)
+                            .to(Sauce::Table, Sauce::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(SauceBatch::LabelDate).date())
+                    .col(ColumnDef::new(SauceBatch::LotNumber).string())
+                    .col(
+                        ColumnDef::new(SauceBatch::NewRecipe)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(ColumnDef::new(SauceBatch::AddedBy).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(SauceBatch::Table, SauceBatch::AddedBy)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .col(
+                        ColumnDef::new(SauceBatch::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-sauce_batch-sauce")
+                    .table(SauceBatch::Table)
+                    .col(SauceBatch::Sauce)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Review::Table)
+                    .add_column(ColumnDef::new(Review::Batch).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        drop_column(manager, Review::Table, Review::Batch).await?;
+
+        manager
+            .drop_table(Table::drop().table(SauceBatch::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
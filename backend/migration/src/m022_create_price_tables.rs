@@ -0,0 +1,152 @@
+use sea_orm_migration::{prelude::*, sea_query::Table};
+
+use crate::{m001_create_user_tables::User, m002_create_sauce_tables::Sauce};
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "022_create_price_tables"
+    }
+}
+
+/// A shop, online or otherwise, that sells sauces
+#[derive(Iden)]
+pub enum Retailer {
+    Table,
+    Id,
+    Name,
+    Website,
+}
+
+/// The price a sauce was seen selling for at a retailer on a given day
+#[derive(Iden)]
+pub enum PriceObservation {
+    Table,
+    Id,
+    Sauce,
+    Retailer,
+    /// In the currency's minor unit, e.g. pence or cents
+    PriceMinor,
+    Currency,
+    SizeMl,
+    ObservedOn,
+    SubmittedBy,
+    CreatedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Retailer::Table)
+                    .col(
+                        ColumnDef::new(Retailer::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Retailer::Name)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Retailer::Website).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(PriceObservation::Table)
+                    .col(
+                        ColumnDef::new(PriceObservation::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PriceObservation::Sauce).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(PriceObservation::Table, PriceObservation::Sauce)
+                            .to(Sauce::Table, Sauce::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(PriceObservation::Retailer)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(PriceObservation::Table, PriceObservation::Retailer)
+                            .to(Retailer::Table, Retailer::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(PriceObservation::PriceMinor)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PriceObservation::Currency)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PriceObservation::SizeMl)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PriceObservation::ObservedOn)
+                            .date()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(PriceObservation::SubmittedBy).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(PriceObservation::Table, PriceObservation::SubmittedBy)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .col(
+                        ColumnDef::new(PriceObservation::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-price_observation-sauce")
+                    .table(PriceObservation::Table)
+                    .col(PriceObservation::Sauce)
+                    .col(PriceObservation::ObservedOn)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PriceObservation::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Retailer::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
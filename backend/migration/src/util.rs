@@ -0,0 +1,31 @@
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{ConnectionTrait, Statement},
+};
+
+/// Drop a single column from a table.
+///
+/// sea-query refuses to generate `DROP COLUMN` statements for sqlite, even though sqlite has
+/// supported them since 3.35, so the statement is written out by hand.
+pub async fn drop_column<T, C>(
+    manager: &SchemaManager<'_>,
+    table: T,
+    column: C,
+) -> Result<(), DbErr>
+where
+    T: Iden,
+    C: Iden,
+{
+    let sql = format!(
+        "ALTER TABLE \"{}\" DROP COLUMN \"{}\"",
+        table.to_string(),
+        column.to_string()
+    );
+
+    manager
+        .get_connection()
+        .execute(Statement::from_string(manager.get_database_backend(), sql))
+        .await?;
+
+    Ok(())
+}
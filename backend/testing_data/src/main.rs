@@ -22,6 +22,28 @@ const SAUCES: [(&str, &str); 13] = [
     ("Melinda's", "Chipotle Pepper Hot Sauce"),
 ];
 
+const SAUCE_PEPPERS: [(&str, &[&str]); 13] = [
+    ("Scorpion Pepper and Passion Fruit", &["Trinidad Scorpion"]),
+    ("Bhut Jolokia", &["Bhut Jolokia"]),
+    ("Peri Peri Hot", &["African Bird's Eye"]),
+    ("Green Savina Hebanero Sweet Heat Sauce", &["Habanero"]),
+    ("Reaper Habanero", &["Carolina Reaper", "Habanero"]),
+    ("Golden Bonnet", &["Scotch Bonnet"]),
+    ("Regret", &["Carolina Reaper", "Trinidad Scorpion"]),
+    (
+        "Nekrogoblikon's Goblin Sauce",
+        &["Habanero", "Ghost Pepper"],
+    ),
+    ("Garlic Reaper", &["Carolina Reaper"]),
+    ("Headless Horseradish", &["Habanero"]),
+    ("Original Habanero XXXtra Hot Pepper Sauce", &["Habanero"]),
+    (
+        "Amarillo Habanero Hot Mustard Sauce",
+        &["Habanero", "Aji Amarillo"],
+    ),
+    ("Chipotle Pepper Hot Sauce", &["Jalapeño"]),
+];
+
 const AXES: [(&str, &str, &str); 4] = [
     ("Overall", "Would actively avoid trying again", "The best"),
     ("Heat", "No heat at all", "Insanely hot"),
@@ -97,6 +119,46 @@ async fn insert_sauces(db: &DatabaseConnection) -> Result<()> {
     Ok(())
 }
 
+async fn insert_peppers(db: &DatabaseConnection) -> Result<()> {
+    for (sauce_name, peppers) in SAUCE_PEPPERS {
+        let sauce_id = entity::sauce::Entity::find()
+            .filter(entity::sauce::Column::Name.eq(sauce_name))
+            .one(db)
+            .await?
+            .unwrap()
+            .id;
+
+        for &pepper_name in peppers {
+            let existing = entity::pepper::Entity::find()
+                .filter(entity::pepper::Column::Name.eq(pepper_name))
+                .one(db)
+                .await?;
+
+            let pepper_id = match existing {
+                Some(pepper) => pepper.id,
+                None => {
+                    entity::pepper::ActiveModel {
+                        name: Set(pepper_name.to_string()),
+                        ..Default::default()
+                    }
+                    .insert(db)
+                    .await?
+                    .id
+                }
+            };
+
+            entity::sauce_pepper::ActiveModel {
+                sauce: Set(sauce_id),
+                pepper: Set(pepper_id),
+            }
+            .insert(db)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
 async fn insert_rating_axes(db: &DatabaseConnection) -> Result<()> {
     for (name, min_desc, max_desc) in AXES {
         entity::rating_axis::ActiveModel {
@@ -115,9 +177,11 @@ async fn insert_rating_axes(db: &DatabaseConnection) -> Result<()> {
 }
 
 async fn insert_users(db: &DatabaseConnection) -> Result<()> {
-    for name in USERNAMES {
+    for (i, name) in USERNAMES.iter().enumerate() {
         entity::user::ActiveModel {
             username: Set(name.to_string()),
+            // So there's someone to try out the moderation queue with
+            is_moderator: Set(i == 0),
             ..Default::default()
         }
         .insert(db)
@@ -149,13 +213,13 @@ async fn insert_random_review(db: &DatabaseConnection) -> Result<()> {
         .unwrap()
         .id;
 
-    let epoch = Utc.ymd(2022, 1, 1).and_hms(0, 0, 0);
+    let epoch = Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
     let timestamp = epoch + chrono::Duration::seconds(rng.gen_range(0..(3600 * 24 * 365)));
 
     let text_length = rng.gen_range(5..50);
     let text = Some(lipsum::lipsum_words(text_length));
 
-    entity::review::ActiveModel {
+    let review = entity::review::ActiveModel {
         sauce: Set(sauce),
         user: Set(user),
         timestamp: Set(timestamp),
@@ -165,6 +229,21 @@ async fn insert_random_review(db: &DatabaseConnection) -> Result<()> {
     .insert(db)
     .await?;
 
+    for axis in entity::rating_axis::Entity::find().all(db).await? {
+        // Ratings are given in half-point increments
+        let steps = ((axis.max_value - axis.min_value) * 2.0) as i32;
+        let rating = axis.min_value + rng.gen_range(0..=steps) as f64 / 2.0;
+
+        entity::review_rating::ActiveModel {
+            review: Set(review.id),
+            rating_axis: Set(axis.id),
+            rating: Set(rating),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+    }
+
     Ok(())
 }
 
@@ -176,6 +255,7 @@ async fn main() -> Result<()> {
 
     insert_manufacturers(&db).await?;
     insert_sauces(&db).await?;
+    insert_peppers(&db).await?;
     insert_rating_axes(&db).await?;
     insert_users(&db).await?;
 
@@ -26,6 +26,10 @@ const routes = [
     { path: '/profile', component: Profile },
     { path: '/sauces', component: Sauces },
     { path: '/review/submit', component: ReviewSubmit },
+    // Shared links to a sauce or review, which the api serves with link preview metadata. The app
+    // doesn't have a page for a single sauce or review, so these open the sauce list.
+    { path: '/sauce/:id(\\d+)', component: Sauces },
+    { path: '/review/:id(\\d+)', component: Sauces },
   ]
 
 const router = VueRouter.createRouter({